@@ -1,6 +1,9 @@
 #![allow(missing_docs)]
 #![allow(clippy::unwrap_used)]
 
+//! Run with `--features simd` to compare SIMD and scalar implementations: the "scalar" cases always
+//! use the scalar implementation.
+
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 fn encode_slice() {
@@ -31,6 +34,37 @@ fn encode_array() {
     base32_fs::encode(black_box(b"12345678901234567890123456789012"), &mut output);
 }
 
+struct ArrayBulk {
+    array: [u8; base32_fs::encoded_len(32)],
+    offset: usize,
+}
+
+impl base32_fs::Output for ArrayBulk {
+    fn push(&mut self, ch: u8) {
+        self.array[self.offset] = ch;
+        self.offset += 1;
+    }
+
+    fn push_slice(&mut self, bytes: &[u8]) {
+        self.array[self.offset..self.offset + bytes.len()].copy_from_slice(bytes);
+        self.offset += bytes.len();
+    }
+}
+
+fn encode_array_bulk() {
+    let mut output = ArrayBulk {
+        array: [0_u8; base32_fs::encoded_len(32)],
+        offset: 0,
+    };
+    base32_fs::encode(black_box(b"12345678901234567890123456789012"), &mut output);
+}
+
+fn encode_vec() {
+    let mut output: Vec<u8> = Vec::with_capacity(base32_fs::encoded_len(32));
+    base32_fs::encode(black_box(b"12345678901234567890123456789012"), &mut output);
+    black_box(output);
+}
+
 fn decode_slice() {
     let mut output = [0_u8; 32];
     base32_fs::decode(
@@ -40,10 +74,90 @@ fn decode_slice() {
     .unwrap();
 }
 
+fn decode_vec() {
+    let mut output: Vec<u8> = Vec::with_capacity(32);
+    base32_fs::decode(
+        black_box(b"64s36d1n6rvkge9g64s36d1n6rvkge9g64s36d1n6rvkge9g64s0".as_slice()),
+        &mut output,
+    )
+    .unwrap();
+    black_box(output);
+}
+
+fn encode_bulk(input: &[u8]) {
+    let mut output: Vec<u8> = Vec::with_capacity(base32_fs::encoded_len(input.len()));
+    base32_fs::encode(black_box(input), &mut output);
+    black_box(output);
+}
+
+fn decode_bulk(input: &[u8]) {
+    let mut output: Vec<u8> = Vec::with_capacity(base32_fs::decoded_len(input.len()).unwrap());
+    base32_fs::decode(black_box(input), &mut output).unwrap();
+    black_box(output);
+}
+
+/// Slice input that doesn't implement `next_chunks`, i.e. it is always processed by the scalar
+/// implementation.
+struct ScalarInput<'a>(&'a [u8]);
+
+impl<const N: usize> base32_fs::Input<N> for ScalarInput<'_> {
+    fn next_chunk(&mut self) -> Option<&[u8]> {
+        base32_fs::Input::<N>::next_chunk(&mut self.0)
+    }
+
+    fn remainder(&self) -> &[u8] {
+        self.0
+    }
+}
+
+fn encode_bulk_scalar(input: &[u8]) {
+    let mut output: Vec<u8> = Vec::with_capacity(base32_fs::encoded_len(input.len()));
+    base32_fs::encode(ScalarInput(black_box(input)), &mut output);
+    black_box(output);
+}
+
+fn decode_bulk_scalar(input: &[u8]) {
+    let mut output: Vec<u8> = Vec::with_capacity(base32_fs::decoded_len(input.len()).unwrap());
+    base32_fs::decode(ScalarInput(black_box(input)), &mut output).unwrap();
+    black_box(output);
+}
+
+fn encode_bulk_source(input: &[u8]) {
+    let mut output: Vec<u8> = Vec::with_capacity(base32_fs::encoded_len(input.len()));
+    base32_fs::encode(base32_fs::SourceInput::new(black_box(input)), &mut output);
+    black_box(output);
+}
+
+fn decode_bulk_source(input: &[u8]) {
+    let mut output: Vec<u8> = Vec::with_capacity(base32_fs::decoded_len(input.len()).unwrap());
+    base32_fs::decode(base32_fs::SourceInput::new(black_box(input)), &mut output).unwrap();
+    black_box(output);
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("decode slice", |b| b.iter(decode_slice));
+    c.bench_function("decode vec", |b| b.iter(decode_vec));
     c.bench_function("encode slice", |b| b.iter(encode_slice));
+    c.bench_function("encode vec", |b| b.iter(encode_vec));
     c.bench_function("encode array", |b| b.iter(encode_array));
+    c.bench_function("encode array bulk", |b| b.iter(encode_array_bulk));
+    let bytes: Vec<u8> = (0..4096_u32).map(|i| (i * 7919 % 251) as u8).collect();
+    let mut chars: Vec<u8> = Vec::new();
+    base32_fs::encode(&bytes, &mut chars);
+    c.bench_function("encode 4 KiB", |b| b.iter(|| encode_bulk(&bytes)));
+    c.bench_function("decode 4 KiB", |b| b.iter(|| decode_bulk(&chars)));
+    c.bench_function("encode 4 KiB scalar", |b| {
+        b.iter(|| encode_bulk_scalar(&bytes))
+    });
+    c.bench_function("decode 4 KiB scalar", |b| {
+        b.iter(|| decode_bulk_scalar(&chars))
+    });
+    c.bench_function("encode 4 KiB source", |b| {
+        b.iter(|| encode_bulk_source(&bytes))
+    });
+    c.bench_function("decode 4 KiB source", |b| {
+        b.iter(|| decode_bulk_source(&chars))
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -1,6 +1,151 @@
+use core::marker::PhantomData;
+
 // Crockford's base32.
 pub(crate) const CHARS: [u8; 32] = *b"0123456789abcdefghjkmnpqrstvwxyz";
 
+/// BASE32 alphabet.
+///
+/// Use alphabets other than the default one via [`encode_with`](crate::encode_with) and
+/// [`decode_with`](crate::decode_with). The alphabet is verified at compile time: all characters
+/// have to be ASCII and distinct.
+///
+/// ```rust
+/// use base32_fs::decode_with;
+/// use base32_fs::encode_with;
+/// use base32_fs::Base32Hex;
+///
+/// let mut chars: Vec<u8> = Vec::new();
+/// encode_with::<Base32Hex, _, _>(b"hello", &mut chars);
+/// assert_eq!(b"D1IMOR3F", chars.as_slice());
+/// let mut bytes: Vec<u8> = Vec::new();
+/// decode_with::<Base32Hex, _, _>(&chars, &mut bytes).unwrap();
+/// assert_eq!(b"hello", bytes.as_slice());
+/// ```
+///
+/// Alphabets with duplicate characters are rejected.
+///
+/// ```rust,compile_fail
+/// use base32_fs::encode_with;
+/// use base32_fs::Alphabet;
+///
+/// struct Duplicates;
+///
+/// impl Alphabet for Duplicates {
+///     const CHARS: [u8; 32] = *b"0123456789abcdefghijklmnopqrstu0";
+/// }
+///
+/// let mut chars: Vec<u8> = Vec::new();
+/// encode_with::<Duplicates, _, _>(b"hello", &mut chars);
+/// ```
+pub trait Alphabet {
+    /// The characters ordered by their values.
+    const CHARS: [u8; 32];
+}
+
+/// Crockford's alphabet in lowercase without "i", "l", "o" and "u".
+///
+/// This is the default alphabet that is used by [`encode`](crate::encode),
+/// [`decode`](crate::decode) and all other functions without `_with` suffix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Crockford;
+
+impl Alphabet for Crockford {
+    const CHARS: [u8; 32] = CHARS;
+}
+
+/// The alphabet from [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648#section-6).
+///
+/// This alphabet doesn't preserve the sorting order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rfc4648;
+
+impl Alphabet for Rfc4648 {
+    const CHARS: [u8; 32] = *b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+}
+
+/// The "extended hex" alphabet from [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648#section-7).
+///
+/// Like the default alphabet this one preserves the sorting order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Base32Hex;
+
+impl Alphabet for Base32Hex {
+    const CHARS: [u8; 32] = *b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+}
+
+/// The [z-base-32](https://philzimmermann.com/docs/human-oriented-base-32-encoding.txt) alphabet.
+///
+/// This alphabet doesn't preserve the sorting order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ZBase32;
+
+impl Alphabet for ZBase32 {
+    const CHARS: [u8; 32] = *b"ybndrfg8ejkmcpqxot1uwisza345h769";
+}
+
+/// Returns `true` if the characters of the alphabet are strictly sorted in ASCII order.
+///
+/// Encoding with such an alphabet preserves the sorting order of the byte sequences. The function
+/// can be used in constant expressions.
+///
+/// ```rust
+/// use base32_fs::is_order_preserving;
+/// use base32_fs::Base32Hex;
+/// use base32_fs::Crockford;
+/// use base32_fs::Rfc4648;
+///
+/// const _: () = assert!(is_order_preserving::<Crockford>());
+/// const _: () = assert!(is_order_preserving::<Base32Hex>());
+/// const _: () = assert!(!is_order_preserving::<Rfc4648>());
+/// ```
+pub const fn is_order_preserving<A: Alphabet>() -> bool {
+    let chars = A::CHARS;
+    let mut i = 1;
+    while i < chars.len() {
+        if chars[i - 1] >= chars[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Index value of the characters that are not in the alphabet.
+pub(crate) const INVALID_INDEX: u8 = 0xff;
+
+/// Compile-time tables of the alphabet.
+pub(crate) struct Table<A: ?Sized>(PhantomData<A>);
+
+impl<A: Alphabet + ?Sized> Table<A> {
+    /// Character to index mapping.
+    ///
+    /// Fails to compile if the alphabet contains duplicate or non-ASCII characters.
+    pub(crate) const INDICES: [u8; 256] = {
+        let mut indices = [INVALID_INDEX; 256];
+        let mut i = 0;
+        while i < A::CHARS.len() {
+            let ch = A::CHARS[i];
+            assert!(ch.is_ascii(), "The alphabet contains non-ASCII characters");
+            assert!(
+                indices[ch as usize] == INVALID_INDEX,
+                "The alphabet contains duplicate characters"
+            );
+            indices[ch as usize] = i as u8;
+            i += 1;
+        }
+        indices
+    };
+
+    /// `true` if the alphabet is the default one.
+    pub(crate) const IS_DEFAULT: bool = {
+        let mut i = 0;
+        while i < CHARS.len() && A::CHARS[i] == CHARS[i] {
+            i += 1;
+        }
+        i == CHARS.len()
+    };
+}
+
 #[inline]
 pub(crate) const fn char_index(ch: u8) -> u8 {
     // Here we choose a range based on the value of the 6th bit.
@@ -48,4 +193,30 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_tables() {
+        check_table::<Crockford>();
+        check_table::<Rfc4648>();
+        check_table::<Base32Hex>();
+        check_table::<ZBase32>();
+        const {
+            assert!(Table::<Crockford>::IS_DEFAULT);
+            assert!(!Table::<Base32Hex>::IS_DEFAULT);
+            assert!(is_order_preserving::<Crockford>());
+            assert!(is_order_preserving::<Base32Hex>());
+            assert!(!is_order_preserving::<Rfc4648>());
+            assert!(!is_order_preserving::<ZBase32>());
+        }
+    }
+
+    fn check_table<A: Alphabet>() {
+        for ch in 0..=u8::MAX {
+            let index = Table::<A>::INDICES[ch as usize];
+            match A::CHARS.iter().position(|c| *c == ch) {
+                Some(i) => assert_eq!(i, index as usize),
+                None => assert_eq!(INVALID_INDEX, index),
+            }
+        }
+    }
 }
@@ -0,0 +1,186 @@
+use crate::decode;
+use crate::encode;
+use crate::encoded_len;
+use crate::DecodeError;
+use crate::Input;
+use crate::IntoInput;
+use crate::Output;
+
+/// Encode fixed-size byte array using BASE32 encoding.
+///
+/// Returns stack-allocated string buffer. This is the preferred way of encoding hashes.
+pub fn encode_array<const N: usize>(input: &[u8; N]) -> EncodedArray<N> {
+    let mut bytes = [[0_u8; N]; 2];
+    let len = encoded_len(N);
+    encode(&input[..], &mut &mut bytes.as_flattened_mut()[..len]);
+    EncodedArray { bytes }
+}
+
+/// Decode `input` byte sequence using BASE32 encoding into fixed-size byte array.
+///
+/// Returns [`LengthMismatch`](crate::DecodeErrorKind::LengthMismatch) error if the length of the
+/// decoded byte sequence is not equal to `N`.
+pub fn decode_array<const N: usize>(input: impl IntoInput<8>) -> Result<[u8; N], DecodeError> {
+    let mut input = input.into_input();
+    let mut output = ArrayOutput {
+        array: [0_u8; N],
+        len: 0,
+    };
+    decode(&mut input, &mut output)?;
+    if output.len != N {
+        // The input is valid, i.e. its length is the encoded length of the decoded bytes.
+        let e = DecodeError::length_mismatch(encoded_len(output.len), N, output.len);
+        return Err(input.map_error(e));
+    }
+    Ok(output.array)
+}
+
+/// BASE32-encoded fixed-size byte array.
+///
+/// Returned by [`encode_array`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EncodedArray<const N: usize> {
+    // BASE32-encoded string is at most twice as long as the original byte sequence.
+    bytes: [[u8; N]; 2],
+}
+
+impl<const N: usize> EncodedArray<N> {
+    /// Get the encoded string.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: The buffer contains only ASCII characters from the alphabet.
+        unsafe { core::str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Get the encoded string as byte slice.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes.as_flattened()[..encoded_len(N)]
+    }
+
+    /// Get the encoded string as file system path.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn as_path(&self) -> &std::path::Path {
+        std::path::Path::new(self.as_str())
+    }
+}
+
+impl<const N: usize> AsRef<str> for EncodedArray<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for EncodedArray<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<const N: usize> AsRef<std::path::Path> for EncodedArray<N> {
+    fn as_ref(&self) -> &std::path::Path {
+        self.as_path()
+    }
+}
+
+impl<const N: usize> core::fmt::Display for EncodedArray<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for EncodedArray<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Fixed-size output that counts the bytes that don't fit.
+struct ArrayOutput<const N: usize> {
+    array: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Output for ArrayOutput<N> {
+    fn push(&mut self, ch: u8) {
+        if let Some(dst) = self.array.get_mut(self.len) {
+            *dst = ch;
+        }
+        self.len += 1;
+    }
+
+    #[inline]
+    fn push_slice(&mut self, bytes: &[u8]) {
+        if let Some(dst) = self.array.get_mut(self.len..) {
+            let n = dst.len().min(bytes.len());
+            dst[..n].copy_from_slice(&bytes[..n]);
+        }
+        self.len += bytes.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use arbtest::arbtest;
+
+    use crate::DecodeErrorKind;
+
+    #[test]
+    fn test_encode_array() {
+        arbtest(|u| {
+            let input: [u8; 32] = u.arbitrary()?;
+            let actual = encode_array(&input);
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input[..], &mut expected);
+            assert_eq!(&expected[..], actual.as_bytes());
+            assert_eq!(actual.as_str(), actual.to_string());
+            #[cfg(feature = "std")]
+            assert_eq!(std::path::Path::new(actual.as_str()), actual.as_path());
+            assert_eq!(input, decode_array::<32>(actual.as_bytes()).unwrap());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_encode_array_sizes() {
+        assert_eq!("", encode_array(&[]).as_str());
+        assert_eq!("04", encode_array(&[1]).as_str());
+        assert_eq!("d1jprv3f", encode_array(b"hello").as_str());
+        assert_eq!(encoded_len(64), encode_array(&[0xff; 64]).as_str().len());
+    }
+
+    #[test]
+    fn test_decode_array() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut encoded: Vec<u8> = Vec::new();
+            encode(&input[..], &mut encoded);
+            let result = decode_array::<20>(&encoded[..]);
+            if input.len() == 20 {
+                assert_eq!(&input[..], &result.unwrap()[..]);
+            } else {
+                let e = result.unwrap_err();
+                assert_eq!(
+                    DecodeErrorKind::LengthMismatch {
+                        expected: 20,
+                        actual: input.len()
+                    },
+                    e.kind()
+                );
+                assert_eq!(encoded.len(), e.index());
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_decode_array_invalid() {
+        let e = decode_array::<5>(&b"d1jprv3i"[..]).unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidChar, e.kind());
+        assert_eq!(7, e.index());
+    }
+}
@@ -0,0 +1,349 @@
+use crate::decode;
+use crate::decoded_len;
+use crate::validate;
+use crate::DecodeError;
+use crate::Output;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Validated BASE32-encoded string slice.
+///
+/// This is a borrowed counterpart of [`Base32String`] much like [`Path`](std::path::Path) is a
+/// borrowed counterpart of [`PathBuf`](std::path::PathBuf). The string is validated only once on
+/// creation, hence decoding never fails.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Base32Str([u8]);
+
+impl Base32Str {
+    /// Validate the supplied BASE32-encoded string.
+    pub fn new<S: AsRef<[u8]> + ?Sized>(s: &S) -> Result<&Self, DecodeError> {
+        let bytes = s.as_ref();
+        validate(bytes)?;
+        // SAFETY: The string is valid.
+        Ok(unsafe { Self::new_unchecked(bytes) })
+    }
+
+    /// Create string slice without validation.
+    ///
+    /// # Safety
+    ///
+    /// The string has to be valid, i.e. [`is_valid`](crate::is_valid) has to return `true`.
+    pub const unsafe fn new_unchecked(bytes: &[u8]) -> &Self {
+        // SAFETY: `Base32Str` is a transparent wrapper around `[u8]`.
+        unsafe { &*(bytes as *const [u8] as *const Self) }
+    }
+
+    /// Get the string as `&str`.
+    pub const fn as_str(&self) -> &str {
+        // SAFETY: Valid BASE32-encoded strings contain only ASCII characters.
+        unsafe { core::str::from_utf8_unchecked(&self.0) }
+    }
+
+    /// Get the string as byte slice.
+    pub const fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Get the string as [`OsStr`](std::ffi::OsStr).
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn as_os_str(&self) -> &std::ffi::OsStr {
+        self.as_str().as_ref()
+    }
+
+    /// Get the string as [`Path`](std::path::Path).
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn as_path(&self) -> &std::path::Path {
+        self.as_str().as_ref()
+    }
+
+    /// Returns the length of the string.
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the string is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the length of the decoded byte sequence.
+    pub const fn decoded_len(&self) -> usize {
+        match decoded_len(self.0.len()) {
+            Some(len) => len,
+            // The length is always valid.
+            None => 0,
+        }
+    }
+
+    /// Decode the string and write the resulting byte sequence to `output`.
+    pub fn decode<O: Output + ?Sized>(&self, output: &mut O) {
+        let result = decode(&self.0, output);
+        debug_assert!(result.is_ok(), "The string is always valid");
+    }
+
+    /// Decode the string into a vector.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn decode_to_vec(&self) -> Vec<u8> {
+        let mut output = Vec::with_capacity(self.decoded_len());
+        self.decode(&mut output);
+        output
+    }
+}
+
+impl AsRef<str> for Base32Str {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for Base32Str {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl AsRef<std::ffi::OsStr> for Base32Str {
+    fn as_ref(&self) -> &std::ffi::OsStr {
+        self.as_os_str()
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl AsRef<std::path::Path> for Base32Str {
+    fn as_ref(&self) -> &std::path::Path {
+        self.as_path()
+    }
+}
+
+impl<'a> TryFrom<&'a str> for &'a Base32Str {
+    type Error = DecodeError;
+
+    fn try_from(other: &'a str) -> Result<Self, Self::Error> {
+        Base32Str::new(other)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for &'a Base32Str {
+    type Error = DecodeError;
+
+    fn try_from(other: &'a [u8]) -> Result<Self, Self::Error> {
+        Base32Str::new(other)
+    }
+}
+
+impl core::fmt::Display for Base32Str {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl core::fmt::Debug for Base32Str {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl alloc::borrow::ToOwned for Base32Str {
+    type Owned = Base32String;
+
+    fn to_owned(&self) -> Self::Owned {
+        Base32String(self.as_str().into())
+    }
+}
+
+/// Validated BASE32-encoded string.
+///
+/// This is an owned counterpart of [`Base32Str`].
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Base32String(String);
+
+#[cfg(feature = "alloc")]
+impl Base32String {
+    /// Validate the supplied BASE32-encoded string.
+    pub fn new(s: String) -> Result<Self, DecodeError> {
+        validate(s.as_bytes())?;
+        Ok(Self(s))
+    }
+
+    /// Encode `input` byte sequence using BASE32 encoding.
+    pub fn encode(input: &[u8]) -> Self {
+        Self(crate::encode_to_string(input))
+    }
+
+    /// Get the string as [`Base32Str`].
+    pub fn as_base32_str(&self) -> &Base32Str {
+        // SAFETY: The string is valid.
+        unsafe { Base32Str::new_unchecked(self.0.as_bytes()) }
+    }
+
+    /// Transform into [`String`].
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::ops::Deref for Base32String {
+    type Target = Base32Str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_base32_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::borrow::Borrow<Base32Str> for Base32String {
+    fn borrow(&self) -> &Base32Str {
+        self.as_base32_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<Base32Str> for Base32String {
+    fn as_ref(&self) -> &Base32Str {
+        self.as_base32_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<str> for Base32String {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for Base32String {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(all(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "std", feature = "alloc"))))]
+impl AsRef<std::ffi::OsStr> for Base32String {
+    fn as_ref(&self) -> &std::ffi::OsStr {
+        self.as_os_str()
+    }
+}
+
+#[cfg(all(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "std", feature = "alloc"))))]
+impl AsRef<std::path::Path> for Base32String {
+    fn as_ref(&self) -> &std::path::Path {
+        self.as_path()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for Base32String {
+    type Error = DecodeError;
+
+    fn try_from(other: String) -> Result<Self, Self::Error> {
+        Self::new(other)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&Base32Str> for Base32String {
+    fn from(other: &Base32Str) -> Self {
+        alloc::borrow::ToOwned::to_owned(other)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Base32String> for String {
+    fn from(other: Base32String) -> Self {
+        other.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::str::FromStr for Base32String {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Base32Str::new(s)?.into())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Display for Base32String {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for Base32String {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use alloc::borrow::ToOwned;
+    use alloc::string::ToString;
+    use arbtest::arbtest;
+    use std::ffi::OsStr;
+    use std::path::Path;
+
+    use crate::is_valid;
+    use crate::DecodeErrorKind;
+
+    #[test]
+    fn test_base32_str() {
+        arbtest(|u| {
+            let bytes: Vec<u8> = u.arbitrary()?;
+            let string = Base32String::encode(&bytes);
+            let s: &Base32Str = &string;
+            assert_eq!(bytes.len(), s.decoded_len());
+            assert_eq!(bytes, s.decode_to_vec());
+            assert_eq!(s, Base32Str::new(s.as_str()).unwrap());
+            assert_eq!(OsStr::new(s.as_str()), s.as_os_str());
+            assert_eq!(Path::new(s.as_str()), s.as_path());
+            assert_eq!(string, s.to_owned());
+            assert_eq!(string, s.to_string().parse().unwrap());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_validation() {
+        arbtest(|u| {
+            let bytes: Vec<u8> = u.arbitrary()?;
+            let result = Base32Str::new(&bytes);
+            assert_eq!(is_valid(&bytes), result.is_ok());
+            if let Ok(s) = result {
+                assert_eq!(&bytes[..], s.as_bytes());
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_errors() {
+        let e = Base32Str::new("d1jprv3i").unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidChar, e.kind());
+        let e = Base32String::new("01".into()).unwrap_err();
+        assert_eq!(DecodeErrorKind::NonCanonical, e.kind());
+        let e = "d1j".parse::<Base32String>().unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidLength, e.kind());
+    }
+}
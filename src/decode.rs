@@ -1,81 +1,326 @@
 use crate::char_index;
 use crate::is_valid_char;
+use crate::Alphabet;
 use crate::DecodeError;
 use crate::Input;
+use crate::IntoInput;
 use crate::Output;
+use crate::Table;
+use crate::TryDecodeError;
+use crate::TryOutput;
+use crate::CHARS;
+use crate::INVALID_INDEX;
+
+macro_rules! byte {
+    (0, $a: ident, $b: ident) => {
+        ($a << 3) | (($b >> 2) & 0b111)
+    };
+    (1, $b: ident, $c: ident, $d: ident) => {
+        (($b & 0b11) << 6) | ($c << 1) | ($d >> 4)
+    };
+    (2, $d: ident, $e: ident) => {
+        (($d & 0b1111) << 4) | ($e >> 1)
+    };
+    (3, $e: ident, $f: ident, $g: ident) => {
+        (($e & 0b1) << 7) | ($f << 2) | ($g >> 3)
+    };
+    (4, $g: ident, $h: ident) => {
+        (($g & 0b111) << 5) | $h
+    };
+}
 
 /// Decode `input` byte sequence using BASE32 encoding and write the resulting byte sequence to
 /// `output`.
-pub fn decode<I: Input<8>, O: Output + ?Sized>(
-    mut input: I,
+///
+/// The input is validated chunk by chunk, i.e. on error the output might contain the bytes decoded
+/// so far. Use [`decode_atomic`] if this is not desirable.
+pub fn decode<I: IntoInput<8>, O: Output + ?Sized>(
+    input: I,
     output: &mut O,
 ) -> Result<(), DecodeError> {
-    macro_rules! byte {
-        (0, $a: ident, $b: ident) => {
-            ($a << 3) | (($b >> 2) & 0b111)
-        };
-        (1, $b: ident, $c: ident, $d: ident) => {
-            (($b & 0b11) << 6) | ($c << 1) | ($d >> 4)
-        };
-        (2, $d: ident, $e: ident) => {
-            (($d & 0b1111) << 4) | ($e >> 1)
-        };
-        (3, $e: ident, $f: ident, $g: ident) => {
-            (($e & 0b1) << 7) | ($f << 2) | ($g >> 3)
-        };
-        (4, $g: ident, $h: ident) => {
-            (($g & 0b111) << 5) | $h
-        };
+    let mut input = input.into_input();
+    let mut offset = 0;
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if let Some(kernel) = crate::simd::Kernel::detect() {
+        while let Some(block) = input.next_chunks(kernel.decode_block_len() / 8) {
+            if !kernel.decode_block(block, output) {
+                let e = decode_invalid_block(block, offset, output);
+                return Err(input.map_error(e));
+            }
+            offset += block.len();
+        }
     }
     while let Some(chunk) = input.next_chunk() {
-        if !is_valid_chunk(chunk) {
-            return Err(DecodeError);
-        }
-        let a = char_index(chunk[0]);
-        let b = char_index(chunk[1]);
-        let c = char_index(chunk[2]);
-        let d = char_index(chunk[3]);
-        let e = char_index(chunk[4]);
-        let f = char_index(chunk[5]);
-        let g = char_index(chunk[6]);
-        let h = char_index(chunk[7]);
-        output.push(byte!(0, a, b)); // 5 + 3 bits
-        output.push(byte!(1, b, c, d)); // 2 + 5 + 1 bits
-        output.push(byte!(2, d, e)); // 4 + 4 bits
-        output.push(byte!(3, e, f, g)); // 1 + 5 + 2 bits
-        output.push(byte!(4, g, h)); // 3 + 5 bits
+        match decode_chunk(chunk) {
+            Some(bytes) => output.push_slice(&bytes),
+            None => {
+                let e = chunk_error(chunk, offset);
+                return Err(input.map_error(e));
+            }
+        }
+        offset += 8;
     }
     let remainder = input.remainder();
-    if !is_valid_remainder(remainder) {
-        return Err(DecodeError);
+    if let Err(e) = check_remainder(remainder, offset) {
+        return Err(input.map_error(e));
     }
-    let remaining = remainder.len();
-    if remaining == 0 {
-        return Ok(());
+    let (bytes, len) = decode_remainder(remainder);
+    output.push_slice(&bytes[..len]);
+    Ok(())
+}
+
+/// Decode `input` byte sequence using BASE32 encoding with the supplied alphabet and write the
+/// resulting byte sequence to `output`.
+///
+/// With the default alphabet ([`Crockford`](crate::Crockford)) this function is equivalent to
+/// [`decode`]. The errors are the same as the ones returned by [`decode`].
+pub fn decode_with<A: Alphabet, I: IntoInput<8>, O: Output + ?Sized>(
+    input: I,
+    output: &mut O,
+) -> Result<(), DecodeError> {
+    // Verify the alphabet at compile time.
+    let _ = Table::<A>::INDICES;
+    if Table::<A>::IS_DEFAULT {
+        return decode(input, output);
     }
-    let a = char_index(remainder[0]);
-    let b = remainder.get(1).copied().map(char_index).unwrap_or(0);
-    output.push(byte!(0, a, b)); // 5 + 3 bits
-    if remaining == 1 || remaining == 2 {
-        return Ok(());
+    let mut input = input.into_input();
+    let mut offset = 0;
+    while let Some(chunk) = input.next_chunk() {
+        match chunk.first_chunk::<8>().and_then(decode_chunk_with::<A>) {
+            Some(bytes) => output.push_slice(&bytes),
+            None => {
+                let e = chunk_error_with::<A>(chunk, offset);
+                return Err(input.map_error(e));
+            }
+        }
+        offset += 8;
     }
-    let c = remainder.get(2).copied().map(char_index).unwrap_or(0);
-    let d = remainder.get(3).copied().map(char_index).unwrap_or(0);
-    output.push(byte!(1, b, c, d)); // 2 + 5 + 1 bits
-    if remaining == 3 || remaining == 4 {
-        return Ok(());
+    let remainder = input.remainder();
+    if let Err(e) = check_remainder_with::<A>(remainder, offset) {
+        return Err(input.map_error(e));
     }
-    let e = remainder.get(4).copied().map(char_index).unwrap_or(0);
-    output.push(byte!(2, d, e)); // 4 + 4 bits
-    if remaining == 5 {
-        return Ok(());
+    // Zero-extend the remainder.
+    let mut chunk = [A::CHARS[0]; 8];
+    chunk[..remainder.len()].copy_from_slice(remainder);
+    let len = remainder_decoded_len(remainder.len()).unwrap_or(0);
+    if let Some(bytes) = decode_chunk_with::<A>(&chunk) {
+        output.push_slice(&bytes[..len]);
     }
-    let f = remainder.get(5).copied().map(char_index).unwrap_or(0);
-    let g = remainder.get(6).copied().map(char_index).unwrap_or(0);
-    output.push(byte!(3, e, f, g)); // 1 + 5 + 2 bits
     Ok(())
 }
 
+/// Decode the chunks of the invalid block that precede the first invalid chunk.
+///
+/// This way the output is the same as if the block was decoded chunk by chunk. Returns the error
+/// for the first invalid chunk.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[cold]
+fn decode_invalid_block<O: Output + ?Sized>(
+    block: &[u8],
+    offset: usize,
+    output: &mut O,
+) -> DecodeError {
+    let mut offset = offset;
+    for chunk in block.chunks(8) {
+        match decode_chunk(chunk) {
+            Some(bytes) => output.push_slice(&bytes),
+            None => return chunk_error(chunk, offset),
+        }
+        offset += 8;
+    }
+    unreachable!("The kernel rejected a valid block")
+}
+
+/// Decode `input` byte sequence using BASE32 encoding and return the resulting byte sequence.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn decode_to_vec<I: IntoInput<8>>(input: I) -> Result<alloc::vec::Vec<u8>, DecodeError> {
+    let mut output = alloc::vec::Vec::new();
+    decode(input, &mut output)?;
+    Ok(output)
+}
+
+/// Decode `input` byte sequence using BASE32 encoding and return the resulting byte sequence as an
+/// array.
+///
+/// This function can be used to decode constants at compile time, see also
+/// [`base32`](crate::base32).
+///
+/// Returns [`LengthMismatch`](crate::DecodeErrorKind::LengthMismatch) error if the length of the
+/// decoded byte sequence is not equal to `N`.
+pub const fn decode_const<const N: usize>(input: &[u8]) -> Result<[u8; N], DecodeError> {
+    let (chunks, remainder) = input.as_chunks::<8>();
+    let mut i = 0;
+    while i < chunks.len() {
+        if let Err(e) = check_chunk(&chunks[i], i * 8) {
+            return Err(e);
+        }
+        i += 1;
+    }
+    if let Err(e) = check_remainder(remainder, input.len() - remainder.len()) {
+        return Err(e);
+    }
+    match decoded_len(input.len()) {
+        Some(len) if len == N => {}
+        Some(len) => return Err(DecodeError::length_mismatch(input.len(), N, len)),
+        None => return Err(DecodeError::invalid_length(input.len())),
+    }
+    let mut output = [0_u8; N];
+    let (output_chunks, output_remainder) = output.as_chunks_mut::<5>();
+    let mut i = 0;
+    while i < chunks.len() {
+        output_chunks[i] = decode_chunk_scalar(&chunks[i]);
+        i += 1;
+    }
+    let (bytes, len) = decode_remainder(remainder);
+    output_remainder.copy_from_slice(bytes.split_at(len).0);
+    Ok(output)
+}
+
+/// Decode 8 characters as 5 bytes.
+///
+/// Returns `None` if the chunk is invalid.
+#[inline]
+fn decode_chunk(chunk: &[u8]) -> Option<[u8; 5]> {
+    let chunk: &[u8; 8] = chunk.try_into().ok()?;
+    #[cfg(target_pointer_width = "64")]
+    {
+        crate::swar::decode_chunk(chunk)
+    }
+    #[cfg(not(target_pointer_width = "64"))]
+    {
+        if !is_valid_chunk(chunk) {
+            return None;
+        }
+        Some(decode_chunk_scalar(chunk))
+    }
+}
+
+/// Decode 8 valid characters as 5 bytes.
+#[inline]
+pub(crate) const fn decode_chunk_scalar(chunk: &[u8; 8]) -> [u8; 5] {
+    decode_indices([
+        char_index(chunk[0]),
+        char_index(chunk[1]),
+        char_index(chunk[2]),
+        char_index(chunk[3]),
+        char_index(chunk[4]),
+        char_index(chunk[5]),
+        char_index(chunk[6]),
+        char_index(chunk[7]),
+    ])
+}
+
+/// Decode 8 character indices as 5 bytes.
+#[inline]
+const fn decode_indices(indices: [u8; 8]) -> [u8; 5] {
+    let [a, b, c, d, e, f, g, h] = indices;
+    [
+        byte!(0, a, b),    // 5 + 3 bits
+        byte!(1, b, c, d), // 2 + 5 + 1 bits
+        byte!(2, d, e),    // 4 + 4 bits
+        byte!(3, e, f, g), // 1 + 5 + 2 bits
+        byte!(4, g, h),    // 3 + 5 bits
+    ]
+}
+
+/// Decode 8 characters of the supplied alphabet as 5 bytes.
+///
+/// Returns `None` if any of the characters is not in the alphabet.
+#[inline]
+fn decode_chunk_with<A: Alphabet>(chunk: &[u8; 8]) -> Option<[u8; 5]> {
+    let indices = chunk.map(|ch| Table::<A>::INDICES[ch as usize]);
+    if indices.contains(&INVALID_INDEX) {
+        return None;
+    }
+    Some(decode_indices(indices))
+}
+
+/// Decode the last 2, 4, 5 or 7 valid characters of the input.
+///
+/// Returns the bytes and their number.
+#[inline]
+pub(crate) const fn decode_remainder(remainder: &[u8]) -> ([u8; 5], usize) {
+    // Zero-extend the remainder.
+    let mut chunk = [CHARS[0]; 8];
+    chunk
+        .split_at_mut(remainder.len())
+        .0
+        .copy_from_slice(remainder);
+    let len = match remainder_decoded_len(remainder.len()) {
+        Some(len) => len,
+        None => 0,
+    };
+    (decode_chunk_scalar(&chunk), len)
+}
+
+/// Decode `input` byte sequence using BASE32 encoding and write the resulting byte sequence to
+/// `output` only if the whole input is valid.
+///
+/// Unlike [`decode`] this function leaves the output untouched on error. The input is traversed
+/// twice: the first time to [`validate`] it and the second time to decode it.
+pub fn decode_atomic<I: IntoInput<8>, O: Output + ?Sized>(
+    input: I,
+    output: &mut O,
+) -> Result<(), DecodeError>
+where
+    I::Input: Clone,
+{
+    let input = input.into_input();
+    validate(input.clone())?;
+    decode(input, output)
+}
+
+/// Decode `input` byte sequence using BASE32 encoding and write the resulting byte sequence to
+/// `output` only if the whole input is valid and the output has enough space.
+///
+/// Unlike [`decode`] this function doesn't panic and leaves the output untouched on error.
+pub fn try_decode<I: IntoInput<8>, O: TryOutput + ?Sized>(
+    input: I,
+    output: &mut O,
+) -> Result<(), TryDecodeError>
+where
+    I::Input: Clone,
+{
+    let input = input.into_input();
+    let len = validate(input.clone())?;
+    output.try_reserve(len)?;
+    decode(input, output)?;
+    Ok(())
+}
+
+/// Check that `input` is a valid BASE32-encoded string without decoding it.
+///
+/// Returns the length of the decoded byte sequence.
+pub fn validate<I: IntoInput<8>>(input: I) -> Result<usize, DecodeError> {
+    let mut input = input.into_input();
+    let mut offset = 0;
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if let Some(kernel) = crate::simd::Kernel::detect() {
+        while let Some(block) = input.next_chunks(kernel.decode_block_len() / 8) {
+            if !kernel.is_valid_block(block) {
+                let e = chunk_error(block, offset);
+                return Err(input.map_error(e));
+            }
+            offset += block.len();
+        }
+    }
+    while let Some(chunk) = input.next_chunk() {
+        if let Err(e) = check_chunk(chunk, offset) {
+            return Err(input.map_error(e));
+        }
+        offset += 8;
+    }
+    let remainder = input.remainder();
+    if let Err(e) = check_remainder(remainder, offset) {
+        return Err(input.map_error(e));
+    }
+    match decoded_len(offset + remainder.len()) {
+        Some(len) => Ok(len),
+        None => Err(DecodeError::invalid_length(offset + remainder.len())),
+    }
+}
+
 /// Returns the length of the original byte sequence for the given BASE32-encoded string length.
 ///
 /// Returns `None` if `input_len` is invalid (i.e. was not returned by
@@ -103,13 +348,23 @@ const fn remainder_decoded_len(input_len: usize) -> Option<usize> {
 }
 
 /// Returns `true` if the `input` is a valid BASE32-encoded string.
+///
+/// Besides checking the length and the characters this function also checks that the unused bits
+/// of the last character are zero, i.e. that the input is the canonical encoding of some byte
+/// sequence.
 #[inline]
 pub const fn is_valid(input: &[u8]) -> bool {
-    decoded_len(input.len()).is_some() && is_valid_chunk(input)
+    decoded_len(input.len()).is_some() && is_valid_chunk(input) && is_canonical_remainder(input)
 }
 
 #[inline]
 const fn is_valid_chunk(mut input: &[u8]) -> bool {
+    while let Some((chunk, rest)) = input.split_first_chunk::<8>() {
+        if !crate::swar::is_valid_chunk(chunk) {
+            return false;
+        }
+        input = rest;
+    }
     while let [ch, rest @ ..] = input {
         if !is_valid_char(*ch) {
             return false;
@@ -119,16 +374,246 @@ const fn is_valid_chunk(mut input: &[u8]) -> bool {
     true
 }
 
+/// Check that all characters are valid.
+///
+/// The `offset` is the position of the chunk in the input.
+#[inline]
+const fn check_chunk(input: &[u8], offset: usize) -> Result<(), DecodeError> {
+    if is_valid_chunk(input) {
+        return Ok(());
+    }
+    Err(chunk_error(input, offset))
+}
+
+/// Returns the error for the chunk that failed to decode.
+///
+/// The `offset` is the position of the chunk in the input.
+#[cold]
+const fn chunk_error(input: &[u8], offset: usize) -> DecodeError {
+    let mut i = 0;
+    while i < input.len() {
+        if !is_valid_char(input[i]) {
+            return DecodeError::invalid_char(offset + i, input[i]);
+        }
+        i += 1;
+    }
+    // The chunk has wrong length.
+    DecodeError::invalid_length(offset + input.len())
+}
+
+/// Check that all characters are valid, the length is valid and the unused bits are zero.
+///
+/// The `offset` is the position of the remainder in the input.
+#[inline]
+const fn check_remainder(input: &[u8], offset: usize) -> Result<(), DecodeError> {
+    if let Err(e) = check_chunk(input, offset) {
+        return Err(e);
+    }
+    if input.len() >= 8 || remainder_decoded_len(input.len()).is_none() {
+        return Err(DecodeError::invalid_length(offset + input.len()));
+    }
+    if !is_canonical_remainder(input) {
+        let i = input.len() - 1;
+        return Err(DecodeError::non_canonical(offset + i, input[i]));
+    }
+    Ok(())
+}
+
+/// Returns the error for the chunk of the supplied alphabet that failed to decode.
+///
+/// The `offset` is the position of the chunk in the input.
+#[cold]
+fn chunk_error_with<A: Alphabet>(input: &[u8], offset: usize) -> DecodeError {
+    match input
+        .iter()
+        .position(|ch| Table::<A>::INDICES[*ch as usize] == INVALID_INDEX)
+    {
+        Some(i) => DecodeError::invalid_char(offset + i, input[i]),
+        // The chunk has wrong length.
+        None => DecodeError::invalid_length(offset + input.len()),
+    }
+}
+
+/// Check the remainder of the input in the supplied alphabet.
+///
+/// Same as [`check_remainder`] but for any alphabet.
+fn check_remainder_with<A: Alphabet>(input: &[u8], offset: usize) -> Result<(), DecodeError> {
+    let indices = &Table::<A>::INDICES;
+    if input
+        .iter()
+        .any(|ch| indices[*ch as usize] == INVALID_INDEX)
+    {
+        return Err(chunk_error_with::<A>(input, offset));
+    }
+    if input.len() >= 8 || remainder_decoded_len(input.len()).is_none() {
+        return Err(DecodeError::invalid_length(offset + input.len()));
+    }
+    if let (Some(mask), [.., last]) = (unused_bits_mask(input.len()), input) {
+        if indices[*last as usize] & mask != 0 {
+            let i = input.len() - 1;
+            return Err(DecodeError::non_canonical(offset + i, input[i]));
+        }
+    }
+    Ok(())
+}
+
+/// Returns `true` if the unused low bits of the last character of the remainder are zero.
+///
+/// Only the length of the input modulo 8 matters, hence this function can be called with the
+/// whole input as well as with the remainder. All characters have to be valid.
+#[inline]
+const fn is_canonical_remainder(input: &[u8]) -> bool {
+    let Some(mask) = unused_bits_mask(input.len()) else {
+        return true;
+    };
+    let [.., last] = input else {
+        return true;
+    };
+    char_index(*last) & mask == 0
+}
+
+/// Returns the mask of the bits of the last character that are not used when decoding the
+/// remainder of the given length.
 #[inline]
-fn is_valid_remainder(input: &[u8]) -> bool {
-    is_valid_chunk(input) && remainder_decoded_len(input.len()).is_some()
+const fn unused_bits_mask(input_len: usize) -> Option<u8> {
+    match input_len % 8 {
+        2 => Some(0b11),   // 10 bits -> 1 byte
+        4 => Some(0b1111), // 20 bits -> 2 bytes
+        5 => Some(0b1),    // 25 bits -> 3 bytes
+        7 => Some(0b111),  // 35 bits -> 4 bytes
+        _ => None,
+    }
+}
+
+/// Decode `buf` in place using BASE32 encoding.
+///
+/// The decoded byte sequence is written from the front of the buffer and overwrites the input.
+/// Returns the decoded bytes. On error the buffer contains the bytes decoded so far followed by
+/// the rest of the input.
+///
+/// ```rust
+/// use base32_fs::decode_in_place;
+///
+/// let mut buf = *b"d1jprv3f";
+/// assert_eq!(b"hello", decode_in_place(&mut buf).unwrap());
+/// ```
+pub fn decode_in_place(buf: &mut [u8]) -> Result<&mut [u8], DecodeError> {
+    let num_chunks = buf.len() / 8;
+    for i in 0..num_chunks {
+        let mut chunk = [0_u8; 8];
+        chunk.copy_from_slice(&buf[i * 8..][..8]);
+        match decode_chunk(&chunk) {
+            Some(bytes) => buf[i * 5..][..5].copy_from_slice(&bytes),
+            None => return Err(chunk_error(&chunk, i * 8)),
+        }
+    }
+    let offset = num_chunks * 8;
+    check_remainder(&buf[offset..], offset)?;
+    let (bytes, n) = decode_remainder(&buf[offset..]);
+    buf[num_chunks * 5..][..n].copy_from_slice(&bytes[..n]);
+    Ok(&mut buf[..num_chunks * 5 + n])
+}
+
+/// Returns an iterator over the bytes of the decoded `input`.
+///
+/// The bytes are decoded lazily, 5 bytes at a time. The iterator stops after the first error.
+///
+/// ```rust
+/// use base32_fs::decode_iter;
+///
+/// assert!(decode_iter("d1jprv3f").map(Result::unwrap).eq(*b"hello"));
+/// ```
+pub fn decode_iter<I: IntoInput<8>>(input: I) -> DecodeIter<I::Input> {
+    DecodeIter {
+        input: input.into_input(),
+        bytes: [0_u8; 5],
+        pos: 0,
+        len: 0,
+        offset: 0,
+        done: false,
+    }
+}
+
+/// An iterator over the bytes of BASE32-decoded string.
+///
+/// Returned by [`decode_iter`].
+#[derive(Clone)]
+pub struct DecodeIter<I> {
+    input: I,
+    // Decoded bytes of the current chunk.
+    bytes: [u8; 5],
+    pos: usize,
+    len: usize,
+    // The position of the next chunk in the input.
+    offset: usize,
+    done: bool,
 }
 
+impl<I: Input<8>> DecodeIter<I> {
+    /// Decode the next chunk or the remainder.
+    fn fill(&mut self) -> Result<(), DecodeError> {
+        self.pos = 0;
+        self.len = 0;
+        if let Some(chunk) = self.input.next_chunk() {
+            match decode_chunk(chunk) {
+                Some(bytes) => {
+                    self.bytes = bytes;
+                    self.len = 5;
+                    self.offset += 8;
+                    return Ok(());
+                }
+                None => {
+                    let e = chunk_error(chunk, self.offset);
+                    return Err(self.input.map_error(e));
+                }
+            }
+        }
+        self.done = true;
+        let remainder = self.input.remainder();
+        if let Err(e) = check_remainder(remainder, self.offset) {
+            return Err(self.input.map_error(e));
+        }
+        (self.bytes, self.len) = decode_remainder(remainder);
+        Ok(())
+    }
+}
+
+impl<I: Input<8>> Iterator for DecodeIter<I> {
+    type Item = Result<u8, DecodeError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos == self.len {
+            if self.done {
+                return None;
+            }
+            if let Err(e) = self.fill() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+        let byte = self.bytes[self.pos];
+        self.pos += 1;
+        Some(Ok(byte))
+    }
+}
+
+impl<I: Input<8>> core::iter::FusedIterator for DecodeIter<I> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
     use arbtest::arbtest;
 
+    use crate::encode;
+    use crate::Base32Hex;
+    use crate::Crockford;
+    use crate::DecodeErrorKind;
+    use crate::OutputFull;
+    use crate::Rfc4648;
+    use crate::ZBase32;
     use crate::CHARS;
 
     #[test]
@@ -147,4 +632,344 @@ mod tests {
     fn is_valid_chunk_slow(input: &[u8]) -> bool {
         input.iter().all(|b| CHARS.contains(b))
     }
+
+    #[test]
+    fn test_remainder_exhaustive() {
+        for len in [2, 4] {
+            let mut num_accepted = 0;
+            let mut input = [0_u8; 4];
+            let input = &mut input[..len];
+            for i in 0..(1_usize << (5 * len)) {
+                for (j, ch) in input.iter_mut().enumerate() {
+                    *ch = CHARS[(i >> (5 * (len - j - 1))) & 0b11111];
+                }
+                if check_round_trip(input) {
+                    num_accepted += 1;
+                }
+            }
+            // Exactly one encoded string per byte sequence.
+            assert_eq!(1 << (8 * decoded_len(len).unwrap()), num_accepted);
+        }
+        // Enumerating all strings of length 5 and 7 is too slow, hence enumerate all values of
+        // the last character for the fixed prefixes.
+        for len in [2, 4, 5, 7] {
+            let mask = unused_bits_mask(len).unwrap();
+            for prefix in [0, 31, 0b10101, 0b01010] {
+                let mut input = [CHARS[prefix]; 7];
+                let input = &mut input[..len];
+                let mut num_accepted = 0;
+                for (i, ch) in CHARS.iter().copied().enumerate() {
+                    input[len - 1] = ch;
+                    let accepted = check_round_trip(input);
+                    assert_eq!(i as u8 & mask == 0, accepted, "input = {input:?}");
+                    if accepted {
+                        num_accepted += 1;
+                    }
+                }
+                assert_eq!(32 >> mask.count_ones(), num_accepted);
+            }
+        }
+    }
+
+    #[test]
+    fn test_remainder_last_char() {
+        arbtest(|u| {
+            let num_chunks = u.int_in_range(0..=4)?;
+            for remainder_len in [2, 4, 5, 7] {
+                let len = num_chunks * 8 + remainder_len;
+                let mut input = Vec::with_capacity(len);
+                for _ in 0..len {
+                    input.push(*u.choose(&CHARS)?);
+                }
+                let mut num_accepted = 0;
+                for ch in CHARS {
+                    input[len - 1] = ch;
+                    if check_round_trip(&input) {
+                        num_accepted += 1;
+                    }
+                }
+                let mask = unused_bits_mask(len).unwrap();
+                assert_eq!(32 >> mask.count_ones(), num_accepted);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_non_canonical() {
+        for input in [&b"01"[..], b"0001", b"00001", b"0000001", b"000000000z"] {
+            assert!(!is_valid(input), "input = {input:?}");
+            let mut output: Vec<u8> = Vec::new();
+            let error = decode(input, &mut output).unwrap_err();
+            assert_eq!(DecodeErrorKind::NonCanonical, error.kind());
+            assert_eq!(input.len() - 1, error.index());
+            assert_eq!(Some(input[input.len() - 1].into()), error.code_unit());
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        for (input, kind, index, code_unit) in [
+            (&b"0"[..], DecodeErrorKind::InvalidLength, 1, None),
+            (b"00000000000", DecodeErrorKind::InvalidLength, 11, None),
+            (b"0000i000", DecodeErrorKind::InvalidChar, 4, Some(b'i')),
+            (b"00000000A0", DecodeErrorKind::InvalidChar, 8, Some(b'A')),
+            (b"000\xff", DecodeErrorKind::InvalidChar, 3, Some(0xff)),
+            (b"00000000u", DecodeErrorKind::InvalidChar, 8, Some(b'u')),
+        ] {
+            let mut output: Vec<u8> = Vec::new();
+            let error = decode(input, &mut output).unwrap_err();
+            assert_eq!(kind, error.kind(), "input = {input:?}");
+            assert_eq!(index, error.index(), "input = {input:?}");
+            assert_eq!(
+                code_unit.map(u16::from),
+                error.code_unit(),
+                "input = {input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_with() {
+        check_decode_with::<Crockford>();
+        check_decode_with::<Rfc4648>();
+        check_decode_with::<Base32Hex>();
+        check_decode_with::<ZBase32>();
+    }
+
+    fn check_decode_with<A: Alphabet>() {
+        arbtest(|u| {
+            let len = u.arbitrary_len::<u8>()?;
+            let mut input = Vec::with_capacity(len);
+            let mut expected_input = Vec::with_capacity(len);
+            for _ in 0..len {
+                // Characters that are invalid in all alphabets are rare.
+                if u.ratio(1, 100)? {
+                    input.push(b'!');
+                    expected_input.push(b'!');
+                } else {
+                    let i = u.choose_index(CHARS.len())?;
+                    input.push(A::CHARS[i]);
+                    expected_input.push(CHARS[i]);
+                }
+            }
+            let mut expected: Vec<u8> = Vec::new();
+            let expected_result = decode(&expected_input, &mut expected);
+            let mut actual: Vec<u8> = Vec::new();
+            let actual_result = decode_with::<A, _, _>(&input, &mut actual);
+            match (expected_result, actual_result) {
+                (Ok(()), Ok(())) => assert_eq!(expected, actual),
+                // The output might contain different number of bytes decoded so far.
+                (Err(expected), Err(actual)) => {
+                    assert_eq!(expected.kind(), actual.kind());
+                    assert_eq!(expected.index(), actual.index());
+                }
+                (expected, actual) => panic!("expected = {expected:?}, actual = {actual:?}"),
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_decode_in_place() {
+        arbtest(|u| {
+            for input_len in 0..=40 {
+                let mut input: Vec<u8> = (0..input_len)
+                    .map(|_| Ok(CHARS[u.choose_index(CHARS.len())?]))
+                    .collect::<Result<_, _>>()?;
+                if !input.is_empty() && u.ratio(1, 4)? {
+                    // Corrupt the input.
+                    let i = u.choose_index(input.len())?;
+                    input[i] = u.arbitrary()?;
+                }
+                let mut expected: Vec<u8> = Vec::new();
+                let expected_result = decode(&input[..], &mut expected);
+                let mut buf = input.clone();
+                match decode_in_place(&mut buf) {
+                    Ok(actual) => {
+                        assert_eq!(Ok(()), expected_result);
+                        assert_eq!(expected, actual);
+                    }
+                    Err(e) => {
+                        assert_eq!(expected_result, Err(e));
+                        assert_eq!(expected, buf[..expected.len()]);
+                    }
+                }
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_decode_iter() {
+        arbtest(|u| {
+            let bytes: Vec<u8> = u.arbitrary()?;
+            let mut input: Vec<u8> = Vec::new();
+            encode(&bytes, &mut input);
+            if !input.is_empty() && u.arbitrary()? {
+                // Corrupt the input.
+                let i = u.choose_index(input.len())?;
+                input[i] = u.arbitrary()?;
+            }
+            let mut expected: Vec<u8> = Vec::new();
+            let expected_result = decode(&input[..], &mut expected);
+            let mut actual: Vec<u8> = Vec::new();
+            let mut actual_result = Ok(());
+            let mut iter = decode_iter(&input[..]);
+            for result in iter.by_ref() {
+                match result {
+                    Ok(byte) => actual.push(byte),
+                    Err(e) => actual_result = Err(e),
+                }
+            }
+            assert_eq!(expected_result, actual_result);
+            assert_eq!(expected, actual);
+            assert_eq!(None, iter.next());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_error_past_first_block() {
+        arbtest(|u| {
+            let len = u.int_in_range(8..=32)? * 8;
+            let mut input: Vec<u8> = (0..len)
+                .map(|_| u.choose(&CHARS).copied())
+                .collect::<Result<_, _>>()?;
+            let index = u.choose_index(len)?;
+            input[index] = b'!';
+            // The chunks before the invalid one are decoded.
+            let mut expected: Vec<u8> = Vec::new();
+            for chunk in input[..index / 8 * 8].as_chunks::<8>().0 {
+                expected.extend(decode_chunk_scalar(chunk));
+            }
+            let expected_error = DecodeError::invalid_char(index, b'!');
+            let mut actual: Vec<u8> = Vec::new();
+            assert_eq!(Err(expected_error), decode(&input, &mut actual));
+            assert_eq!(expected, actual);
+            let (a, b) = input.split_at(u.choose_index(len + 1)?);
+            let mut actual: Vec<u8> = Vec::new();
+            assert_eq!(Err(expected_error), decode((a, b), &mut actual));
+            assert_eq!(expected, actual);
+            assert_eq!(Err(expected_error), validate(&input));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_decode_atomic() {
+        for input in [
+            &b"d1jprv3f0"[..],
+            b"d1jprv3fd1jprv3f000",
+            b"d1jprv3fd1jpi",
+            b"d1jprv3fd1jprv3fd1jprv3F",
+            b"d1jprv3fd1jp1",
+            b"d1jprv3fd1",
+        ] {
+            let mut vec: Vec<u8> = b"prefix".into();
+            assert!(decode_atomic(input, &mut vec).is_err());
+            assert_eq!(b"prefix", vec.as_slice(), "input = {input:?}");
+            let mut array = [b'_'; 15];
+            assert!(decode_atomic(input, &mut &mut array[..]).is_err());
+            assert_eq!([b'_'; 15], array, "input = {input:?}");
+        }
+        let mut vec: Vec<u8> = b"prefix".into();
+        decode_atomic(&b"d1jprv3f"[..], &mut vec).unwrap();
+        assert_eq!(b"prefixhello", vec.as_slice());
+    }
+
+    #[test]
+    fn test_try_decode() {
+        let input = b"d1jprv3f";
+        let mut output = [b'_'; 4];
+        assert_eq!(
+            Err(TryDecodeError::OutputFull(OutputFull::new(4, 5))),
+            try_decode(input.as_slice(), &mut &mut output[..])
+        );
+        assert_eq!([b'_'; 4], output);
+        let mut output = [b'_'; 5];
+        assert!(matches!(
+            try_decode(&b"d1jprv3fi"[..], &mut &mut output[..]),
+            Err(TryDecodeError::Decode(..))
+        ));
+        assert_eq!([b'_'; 5], output);
+        try_decode(input.as_slice(), &mut &mut output[..]).unwrap();
+        assert_eq!(b"hello", &output);
+    }
+
+    #[test]
+    fn test_decode_const() {
+        const HELLO: Result<[u8; 5], DecodeError> = decode_const(b"d1jprv3f");
+        assert_eq!(Ok(*b"hello"), HELLO);
+        arbtest(|u| {
+            let input: [u8; 13] = u.arbitrary()?;
+            let mut encoded: Vec<u8> = Vec::new();
+            encode(&input, &mut encoded);
+            assert_eq!(Ok(input), decode_const(&encoded));
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            let expected = decode(input.as_slice(), &mut expected).map(|_| expected);
+            let actual = decode_const::<3>(&input).map(Vec::from);
+            match expected {
+                Ok(expected) if expected.len() == 3 => assert_eq!(Ok(expected), actual),
+                Ok(expected) => assert_eq!(
+                    Err(DecodeError::length_mismatch(input.len(), 3, expected.len())),
+                    actual
+                ),
+                Err(e) => assert_eq!(Err(e), actual),
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_validate() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut decoded: Vec<u8> = Vec::new();
+            let expected = decode(input.as_slice(), &mut decoded).map(|_| decoded.len());
+            assert_eq!(expected, validate(input.as_slice()), "input = {input:?}");
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_error_display() {
+        let mut output: Vec<u8> = Vec::new();
+        let error = decode(&b"0000i000"[..], &mut output).unwrap_err();
+        assert_eq!("Invalid BASE32 character 'i' at index 4", error.to_string());
+        let error = decode(&b"000\xff"[..], &mut output).unwrap_err();
+        assert_eq!(
+            "Invalid BASE32 character 0xff at index 3",
+            error.to_string()
+        );
+        let error = decode(&b"01"[..], &mut output).unwrap_err();
+        assert_eq!(
+            "Non-canonical BASE32 character '1' at index 1: unused bits are not zero",
+            error.to_string()
+        );
+        let error = decode(&b"000"[..], &mut output).unwrap_err();
+        assert_eq!(
+            "Invalid BASE32 string length 3: 1, 3 or 6 modulo 8",
+            error.to_string()
+        );
+        let error = decode_const::<4>(b"d1jprv3f").unwrap_err();
+        assert_eq!(
+            "BASE32 string of length 8 decodes to 5 bytes, expected 4",
+            error.to_string()
+        );
+    }
+
+    /// Returns `true` if `input` was accepted by the decoder.
+    fn check_round_trip(input: &[u8]) -> bool {
+        let mut decoded: Vec<u8> = Vec::new();
+        let accepted = decode(input, &mut decoded).is_ok();
+        assert_eq!(accepted, is_valid(input), "input = {input:?}");
+        if accepted {
+            let mut encoded: Vec<u8> = Vec::new();
+            encode(&decoded, &mut encoded);
+            assert_eq!(input, encoded, "decoded = {decoded:?}");
+        }
+        accepted
+    }
 }
@@ -0,0 +1,185 @@
+use core::fmt::Alignment;
+use core::fmt::Formatter;
+use core::fmt::Write;
+
+use crate::encode;
+use crate::encoded_len;
+use crate::FmtOutput;
+use crate::GroupedOutput;
+
+/// Returns an object that displays `input` byte sequence as BASE32-encoded string.
+///
+/// The string is written directly to the formatter without memory allocation. Width, fill and
+/// alignment are supported. Precision truncates the string, i.e. `{:.7}` displays an abbreviated
+/// hash.
+///
+/// ```rust
+/// use base32_fs::display;
+///
+/// assert_eq!("d1jprv3f", format!("{}", display(b"hello")));
+/// assert_eq!("d1jp", format!("{:.4}", display(b"hello")));
+/// assert_eq!("d1jprv3f..", format!("{:.<10}", display(b"hello")));
+/// ```
+pub fn display(input: &[u8]) -> Display<'_> {
+    Display { input }
+}
+
+/// Displays byte sequence as BASE32-encoded string.
+///
+/// Returned by [`display`].
+#[derive(Clone, Copy)]
+pub struct Display<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Display<'a> {
+    /// Returns an object that displays the string with `separator` inserted every `group_len`
+    /// characters.
+    ///
+    /// Width, fill, alignment and precision are ignored. Panics if `group_len` is zero or if
+    /// `separator` is not ASCII.
+    ///
+    /// ```rust
+    /// use base32_fs::display;
+    ///
+    /// assert_eq!("d1jp-rv3f", format!("{}", display(b"hello").grouped(4, b'-')));
+    /// ```
+    pub const fn grouped(self, group_len: usize, separator: u8) -> GroupedDisplay<'a> {
+        assert!(group_len != 0, "The group length is zero");
+        assert!(separator.is_ascii(), "The separator is not ASCII");
+        GroupedDisplay {
+            input: self.input,
+            group_len,
+            separator,
+        }
+    }
+
+    /// Write the first `len` characters of the encoded string.
+    fn write_encoded(&self, f: &mut Formatter, mut len: usize) -> core::fmt::Result {
+        const INPUT_LEN: usize = 64 * 5;
+        let mut buf = [0_u8; encoded_len(INPUT_LEN)];
+        for chunk in self.input.chunks(INPUT_LEN) {
+            if len == 0 {
+                break;
+            }
+            let n = encoded_len(chunk.len());
+            encode(chunk, &mut &mut buf[..n]);
+            let n = n.min(len);
+            // SAFETY: BASE32-encoded string contains only ASCII characters.
+            f.write_str(unsafe { core::str::from_utf8_unchecked(&buf[..n]) })?;
+            len -= n;
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Display for Display<'_> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        let mut len = encoded_len(self.input.len());
+        if let Some(precision) = f.precision() {
+            len = len.min(precision);
+        }
+        let padding = f.width().unwrap_or(0).saturating_sub(len);
+        let (left, right) = match f.align() {
+            None | Some(Alignment::Left) => (0, padding),
+            Some(Alignment::Right) => (padding, 0),
+            Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+        };
+        let fill = f.fill();
+        for _ in 0..left {
+            f.write_char(fill)?;
+        }
+        self.write_encoded(f, len)?;
+        for _ in 0..right {
+            f.write_char(fill)?;
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for Display<'_> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        f.write_char('"')?;
+        self.write_encoded(f, encoded_len(self.input.len()))?;
+        f.write_char('"')
+    }
+}
+
+/// Displays byte sequence as grouped BASE32-encoded string.
+///
+/// Returned by [`Display::grouped`].
+#[derive(Clone, Copy)]
+pub struct GroupedDisplay<'a> {
+    input: &'a [u8],
+    group_len: usize,
+    separator: u8,
+}
+
+impl core::fmt::Display for GroupedDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        let mut output = FmtOutput::new(f);
+        encode(
+            self.input,
+            &mut GroupedOutput::new(&mut output, self.group_len, self.separator),
+        );
+        output.finish().map(|_| ())
+    }
+}
+
+impl core::fmt::Debug for GroupedDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        f.write_char('"')?;
+        core::fmt::Display::fmt(self, f)?;
+        f.write_char('"')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use arbtest::arbtest;
+
+    #[test]
+    fn test_display() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input[..], &mut expected);
+            let expected = String::from_utf8(expected).unwrap();
+            assert_eq!(expected, format!("{}", display(&input)));
+            assert_eq!(format!("{expected:?}"), format!("{:?}", display(&input)));
+            let width: usize = u.int_in_range(0..=1000)?;
+            let precision: usize = u.int_in_range(0..=1000)?;
+            assert_eq!(
+                format!("{expected:width$.precision$}"),
+                format!("{:width$.precision$}", display(&input))
+            );
+            assert_eq!(
+                format!("{expected:*>width$.precision$}"),
+                format!("{:*>width$.precision$}", display(&input))
+            );
+            assert_eq!(
+                format!("{expected:^width$}"),
+                format!("{:^width$}", display(&input))
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_grouped() {
+        assert_eq!("", format!("{}", display(b"").grouped(4, b'-')));
+        assert_eq!("d1jp-r", format!("{}", display(b"hel").grouped(4, b'-')));
+        assert_eq!(
+            "d1jp rv3f",
+            format!("{}", display(b"hello").grouped(4, b' '))
+        );
+        assert_eq!(
+            "\"d1j-prv-3f\"",
+            format!("{:?}", display(b"hello").grouped(3, b'-'))
+        );
+    }
+}
@@ -1,6 +1,39 @@
-use crate::Output;
+use crate::Alphabet;
+use crate::EncodeOutput;
+use crate::Input;
+use crate::IntoInput;
+use crate::OutputFull;
+use crate::Table;
+use crate::TryEncodeOutput;
 use crate::CHARS;
 
+macro_rules! byte {
+    ($chars: expr, 0, $a: ident) => {
+        $chars[($a >> 3) as usize]
+    };
+    ($chars: expr, 1, $a: ident, $b: ident) => {
+        $chars[((($a & 0b111) << 2) | ($b >> 6)) as usize]
+    };
+    ($chars: expr, 2, $b: ident) => {
+        $chars[(($b >> 1) & 0b11111) as usize]
+    };
+    ($chars: expr, 3, $b: ident, $c: ident) => {
+        $chars[((($b & 0b1) << 4) | ($c >> 4)) as usize]
+    };
+    ($chars: expr, 4, $c: ident, $d: ident) => {
+        $chars[((($c & 0b1111) << 1) | ($d >> 7)) as usize]
+    };
+    ($chars: expr, 5, $d: ident) => {
+        $chars[(($d >> 2) & 0b11111) as usize]
+    };
+    ($chars: expr, 6, $d: ident, $e: ident) => {
+        $chars[((($d & 0b11) << 3) | ($e >> 5)) as usize]
+    };
+    ($chars: expr, 7, $e: ident) => {
+        $chars[($e & 0b11111) as usize]
+    };
+}
+
 /// Maximum number of bytes that can be encoded as BASE32.
 pub const MAX_INPUT_LEN: usize = usize::MAX / 8 * 5 + 4;
 
@@ -25,77 +58,293 @@ pub const fn encoded_len(input_len: usize) -> usize {
 
 /// Encode `input` byte sequence using BASE32 encoding and write the resulting byte sequence to
 /// `output`.
-pub fn encode<O: Output + ?Sized>(input: &[u8], output: &mut O) {
-    macro_rules! byte {
-        (0, $a: ident) => {
-            CHARS[($a >> 3) as usize]
-        };
-        (1, $a: ident, $b: ident) => {
-            CHARS[((($a & 0b111) << 2) | ($b >> 6)) as usize]
-        };
-        (2, $b: ident) => {
-            CHARS[(($b >> 1) & 0b11111) as usize]
-        };
-        (3, $b: ident, $c: ident) => {
-            CHARS[((($b & 0b1) << 4) | ($c >> 4)) as usize]
-        };
-        (4, $c: ident, $d: ident) => {
-            CHARS[((($c & 0b1111) << 1) | ($d >> 7)) as usize]
-        };
-        (5, $d: ident) => {
-            CHARS[(($d >> 2) & 0b11111) as usize]
-        };
-        (6, $d: ident, $e: ident) => {
-            CHARS[((($d & 0b11) << 3) | ($e >> 5)) as usize]
-        };
-        (7, $e: ident) => {
-            CHARS[($e & 0b11111) as usize]
-        };
-    }
-    let mut chunks = input.chunks_exact(5);
-    for chunk in chunks.by_ref() {
-        let a = chunk[0];
-        let b = chunk[1];
-        let c = chunk[2];
-        let d = chunk[3];
-        let e = chunk[4];
-        output.push(byte!(0, a)); // 5 bits
-        output.push(byte!(1, a, b)); // 3 + 2 bits
-        output.push(byte!(2, b)); // 5 bits
-        output.push(byte!(3, b, c)); // 1 + 4 bits
-        output.push(byte!(4, c, d)); // 4 + 1 bits
-        output.push(byte!(5, d)); // 5 bits
-        output.push(byte!(6, d, e)); // 2 + 3 bits
-        output.push(byte!(7, e)); // 5 bits
-    }
-    let remainder = chunks.remainder();
-    let remaining = remainder.len();
-    if remaining == 0 {
-        return;
-    }
-    let a = remainder[0];
-    output.push(byte!(0, a)); // 5 bits
-    let b = remainder.get(1).copied().unwrap_or(0);
-    output.push(byte!(1, a, b)); // 3 + 2 bits
-    if remaining == 1 {
-        return;
-    }
-    let c = remainder.get(2).copied().unwrap_or(0);
-    output.push(byte!(2, b)); // 5 bits
-    output.push(byte!(3, b, c)); // 1 + 4 bits
-    if remaining == 2 {
-        return;
-    }
-    let d = remainder.get(3).copied().unwrap_or(0);
-    output.push(byte!(4, c, d)); // 4 + 1 bits
-    if remaining == 3 {
-        return;
-    }
-    let e = remainder.get(4).copied().unwrap_or(0);
-    output.push(byte!(5, d)); // 5 bits
-    output.push(byte!(6, d, e)); // 2 + 3 bits
+///
+/// The input can be non-contiguous, see [`IntoInput`](crate::IntoInput).
+pub fn encode<I: IntoInput<5>, O: EncodeOutput + ?Sized>(input: I, output: &mut O) {
+    let mut input = input.into_input();
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if let Some(kernel) = crate::simd::Kernel::detect() {
+        // Encode large batches first, then the remaining blocks one by one.
+        for count in [64, kernel.encode_block_len() / 5] {
+            while let Some(block) = input.next_chunks(count) {
+                let rest = kernel.encode(block, output);
+                assert!(
+                    rest.is_empty(),
+                    "The input returned {} bytes instead of {} chunks",
+                    block.len(),
+                    count
+                );
+            }
+        }
+    }
+    while let Some(chunk) = input.next_chunk() {
+        output.push_chars(&encode_chunk(exact_chunk(chunk)));
+    }
+    let remainder = input.remainder();
+    if !remainder.is_empty() {
+        let (chars, len) = encode_remainder(remainder);
+        output.push_chars(&chars[..len]);
+    }
+}
+
+/// Encode `input` byte sequence using BASE32 encoding with the supplied alphabet and write the
+/// resulting byte sequence to `output`.
+///
+/// With the default alphabet ([`Crockford`](crate::Crockford)) this function is equivalent to
+/// [`encode`].
+pub fn encode_with<A: Alphabet, I: IntoInput<5>, O: EncodeOutput + ?Sized>(
+    input: I,
+    output: &mut O,
+) {
+    // Verify the alphabet at compile time.
+    let _ = Table::<A>::INDICES;
+    if Table::<A>::IS_DEFAULT {
+        return encode(input, output);
+    }
+    let mut input = input.into_input();
+    while let Some(chunk) = input.next_chunk() {
+        output.push_chars(&encode_chunk_with(&A::CHARS, exact_chunk(chunk)));
+    }
+    let remainder = input.remainder();
+    if !remainder.is_empty() {
+        let (chars, len) = encode_remainder_with(&A::CHARS, remainder);
+        output.push_chars(&chars[..len]);
+    }
+}
+
+/// Encode `input` byte sequence using BASE32 encoding and return the resulting string.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encode_to_string<I: IntoInput<5>>(input: I) -> alloc::string::String
+where
+    I::Input: Clone,
+{
+    let input = input.into_input();
+    let mut output = alloc::string::String::with_capacity(encoded_len(input_len(input.clone())));
+    encode(input, &mut output);
+    output
+}
+
+/// Encode `input` byte sequence using BASE32 encoding and return the resulting byte sequence as an
+/// array.
+///
+/// This function can be used to encode constants at compile time, see also
+/// [`base32_encode`](crate::base32_encode).
+///
+/// Panics if `M` is not equal to [`encoded_len(input.len())`](encoded_len).
+pub const fn encode_const<const M: usize>(input: &[u8]) -> [u8; M] {
+    assert!(
+        M == encoded_len(input.len()),
+        "The output length doesn't match the input length"
+    );
+    let mut output = [0_u8; M];
+    let (chunks, remainder) = input.as_chunks::<5>();
+    let (output_chunks, output_remainder) = output.as_chunks_mut::<8>();
+    let mut i = 0;
+    while i < chunks.len() {
+        output_chunks[i] = encode_chunk_scalar(&chunks[i]);
+        i += 1;
+    }
+    let (chars, len) = encode_remainder(remainder);
+    output_remainder.copy_from_slice(chars.split_at(len).0);
+    output
+}
+
+/// Converts the chunk returned by [`Input::next_chunk`] to an array.
+///
+/// Panics if the chunk doesn't contain exactly 5 bytes.
+#[inline]
+fn exact_chunk(chunk: &[u8]) -> &[u8; 5] {
+    match chunk.try_into() {
+        Ok(chunk) => chunk,
+        Err(_) => panic!("The input returned a chunk of {} bytes", chunk.len()),
+    }
+}
+
+/// Encode 5 bytes as 8 characters.
+#[inline]
+fn encode_chunk(chunk: &[u8; 5]) -> [u8; 8] {
+    #[cfg(target_pointer_width = "64")]
+    {
+        crate::swar::encode_chunk(chunk)
+    }
+    #[cfg(not(target_pointer_width = "64"))]
+    {
+        encode_chunk_scalar(chunk)
+    }
+}
+
+/// Encode 5 bytes as 8 characters.
+#[inline]
+pub(crate) const fn encode_chunk_scalar(chunk: &[u8; 5]) -> [u8; 8] {
+    encode_chunk_with(&CHARS, chunk)
+}
+
+/// Encode 5 bytes as 8 characters of the supplied alphabet.
+#[inline]
+const fn encode_chunk_with(chars: &[u8; 32], chunk: &[u8; 5]) -> [u8; 8] {
+    let [a, b, c, d, e] = *chunk;
+    [
+        byte!(chars, 0, a),    // 5 bits
+        byte!(chars, 1, a, b), // 3 + 2 bits
+        byte!(chars, 2, b),    // 5 bits
+        byte!(chars, 3, b, c), // 1 + 4 bits
+        byte!(chars, 4, c, d), // 4 + 1 bits
+        byte!(chars, 5, d),    // 5 bits
+        byte!(chars, 6, d, e), // 2 + 3 bits
+        byte!(chars, 7, e),    // 5 bits
+    ]
+}
+
+/// Encode the last 1-4 bytes of the input.
+///
+/// Returns the characters and their number.
+#[inline]
+pub(crate) const fn encode_remainder(remainder: &[u8]) -> ([u8; 8], usize) {
+    encode_remainder_with(&CHARS, remainder)
+}
+
+/// Encode the last 1-4 bytes of the input using the supplied alphabet.
+///
+/// Returns the characters and their number.
+#[inline]
+const fn encode_remainder_with(chars: &[u8; 32], remainder: &[u8]) -> ([u8; 8], usize) {
+    // Zero-extend the remainder.
+    let mut chunk = [0_u8; 5];
+    chunk
+        .split_at_mut(remainder.len())
+        .0
+        .copy_from_slice(remainder);
+    (
+        encode_chunk_with(chars, &chunk),
+        encoded_len(remainder.len()),
+    )
+}
+
+/// Encode `input` byte sequence using BASE32 encoding and write the resulting byte sequence to
+/// `output` only if the output has enough space.
+///
+/// Unlike [`encode`] this function doesn't panic and leaves the output untouched if the output is
+/// too small.
+pub fn try_encode<I: IntoInput<5>, O: TryEncodeOutput + ?Sized>(
+    input: I,
+    output: &mut O,
+) -> Result<(), OutputFull>
+where
+    I::Input: Clone,
+{
+    let input = input.into_input();
+    output.try_reserve(encoded_len(input_len(input.clone())))?;
+    encode(input, output);
+    Ok(())
+}
+
+/// Returns the number of bytes in `input`.
+fn input_len<I: Input<5>>(mut input: I) -> usize {
+    let mut len = 0;
+    while let Some(chunks) = input.next_chunks(64) {
+        len += chunks.len();
+    }
+    while let Some(chunk) = input.next_chunk() {
+        len += chunk.len();
+    }
+    len + input.remainder().len()
+}
+
+/// Encode the first `input_len` bytes of `buf` in place using BASE32 encoding.
+///
+/// The buffer is processed from the back, hence the encoded string overwrites the input. Returns
+/// the encoded string that occupies the first [`encoded_len(input_len)`](encoded_len) bytes of the
+/// buffer.
+///
+/// Panics if the buffer is too small.
+///
+/// ```rust
+/// use base32_fs::{encode_in_place, encoded_len};
+///
+/// let mut buf = [0_u8; encoded_len(5)];
+/// buf[..5].copy_from_slice(b"hello");
+/// assert_eq!("d1jprv3f", encode_in_place(&mut buf, 5));
+/// ```
+pub fn encode_in_place(buf: &mut [u8], input_len: usize) -> &mut str {
+    let len = encoded_len(input_len);
+    let buf = &mut buf[..len];
+    let num_chunks = input_len / 5;
+    let (chars, n) = encode_remainder(&buf[num_chunks * 5..input_len]);
+    buf[num_chunks * 8..][..n].copy_from_slice(&chars[..n]);
+    for i in (0..num_chunks).rev() {
+        let mut chunk = [0_u8; 5];
+        chunk.copy_from_slice(&buf[i * 5..][..5]);
+        buf[i * 8..][..8].copy_from_slice(&encode_chunk(&chunk));
+    }
+    // SAFETY: BASE32-encoded string contains only ASCII characters.
+    unsafe { core::str::from_utf8_unchecked_mut(buf) }
+}
+
+/// Returns an iterator over the characters of the BASE32-encoded `input`.
+///
+/// The characters are encoded lazily, 8 characters at a time.
+///
+/// ```rust
+/// use base32_fs::encode_iter;
+///
+/// assert!(encode_iter(b"hello").eq(*b"d1jprv3f"));
+/// ```
+pub fn encode_iter(input: &[u8]) -> EncodeIter<'_> {
+    EncodeIter {
+        input,
+        chars: [0_u8; 8],
+        pos: 0,
+        len: 0,
+    }
+}
+
+/// An iterator over the characters of BASE32-encoded byte sequence.
+///
+/// Returned by [`encode_iter`].
+#[derive(Clone)]
+pub struct EncodeIter<'a> {
+    input: &'a [u8],
+    // Encoded characters of the current chunk.
+    chars: [u8; 8],
+    pos: usize,
+    len: usize,
 }
 
+impl Iterator for EncodeIter<'_> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.len {
+            if self.input.is_empty() {
+                return None;
+            }
+            (self.chars, self.len) = match self.input.split_first_chunk::<5>() {
+                Some((chunk, rest)) => {
+                    self.input = rest;
+                    (encode_chunk(chunk), 8)
+                }
+                None => encode_remainder(core::mem::take(&mut self.input)),
+            };
+            self.pos = 0;
+        }
+        let ch = self.chars[self.pos];
+        self.pos += 1;
+        Some(ch)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.pos + encoded_len(self.input.len());
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for EncodeIter<'_> {}
+
+impl core::iter::FusedIterator for EncodeIter<'_> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,8 +352,15 @@ mod tests {
     use alloc::vec::Vec;
     use arbtest::arbtest;
 
+    use crate::char_index;
     use crate::decode;
     use crate::decoded_len;
+    use crate::is_order_preserving;
+    use crate::is_valid;
+    use crate::Base32Hex;
+    use crate::Crockford;
+    use crate::Rfc4648;
+    use crate::ZBase32;
 
     #[test]
     fn test_encoded_len() {
@@ -138,6 +394,136 @@ mod tests {
         assert_eq!(input, decoded);
     }
 
+    #[test]
+    fn test_encode_inputs() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input, &mut expected);
+            let i = u.choose_index(input.len() + 1)?;
+            let j = u.int_in_range(i..=input.len())?;
+            let (a, rest) = input.split_at(i);
+            let (b, c) = rest.split_at(j - i);
+            let mut actual: Vec<u8> = Vec::new();
+            encode((a, rest), &mut actual);
+            assert_eq!(expected, actual);
+            let mut actual: Vec<u8> = Vec::new();
+            encode([a, b, c], &mut actual);
+            assert_eq!(expected, actual);
+            assert_eq!(expected, encode_to_string([a, b, c]).as_bytes());
+            let mut actual: Vec<u8> = Vec::new();
+            encode(crate::IterInput::new(input.iter().copied()), &mut actual);
+            assert_eq!(expected, actual);
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_short_chunk_panic() {
+        /// Input that returns a chunk of 4 bytes.
+        struct ShortInput;
+
+        impl Input<5> for ShortInput {
+            fn next_chunk(&mut self) -> Option<&[u8]> {
+                Some(b"hell")
+            }
+
+            fn remainder(&self) -> &[u8] {
+                &[]
+            }
+        }
+
+        encode(ShortInput, &mut Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_encode_iter() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input, &mut expected);
+            let mut iter = encode_iter(&input);
+            let mut actual: Vec<u8> = Vec::new();
+            loop {
+                assert_eq!(expected.len() - actual.len(), iter.len());
+                match iter.next() {
+                    Some(ch) => actual.push(ch),
+                    None => break,
+                }
+            }
+            assert_eq!(expected, actual);
+            assert_eq!(None, iter.next());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_encode_in_place() {
+        arbtest(|u| {
+            for input_len in 0..=40 {
+                let input: Vec<u8> = (0..input_len)
+                    .map(|_| u.arbitrary())
+                    .collect::<Result<_, _>>()?;
+                let mut expected: Vec<u8> = Vec::new();
+                encode(&input, &mut expected);
+                let mut buf = input.clone();
+                buf.resize(encoded_len(input_len) + u.int_in_range(0..=10)?, b'_');
+                let actual = encode_in_place(&mut buf, input_len);
+                assert_eq!(&expected[..], actual.as_bytes());
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_try_encode() {
+        let input = *b"hello";
+        let mut output = [b'_'; encoded_len(5) - 1];
+        assert_eq!(
+            Err(OutputFull::new(7, 8)),
+            try_encode(&input, &mut &mut output[..])
+        );
+        assert_eq!([b'_'; encoded_len(5) - 1], output);
+        let mut output = [b'_'; encoded_len(5)];
+        try_encode(&input, &mut &mut output[..]).unwrap();
+        assert_eq!(b"d1jprv3f", &output);
+        let mut output: Vec<u8> = Vec::new();
+        try_encode(&input, &mut output).unwrap();
+        assert_eq!(b"d1jprv3f", output.as_slice());
+        let mut output = [b'_'; encoded_len(5) - 1];
+        assert_eq!(
+            Err(OutputFull::new(7, 8)),
+            try_encode((&input[..2], &input[2..]), &mut &mut output[..])
+        );
+        let mut output: Vec<u8> = Vec::new();
+        try_encode((&input[..2], &input[2..]), &mut output).unwrap();
+        assert_eq!(b"d1jprv3f", output.as_slice());
+    }
+
+    #[test]
+    fn test_encode_const() {
+        const HELLO: [u8; 8] = encode_const(b"hello");
+        assert_eq!(b"d1jprv3f", &HELLO);
+        arbtest(|u| {
+            let input: [u8; 13] = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input, &mut expected);
+            assert_eq!(expected, encode_const::<{ encoded_len(13) }>(&input));
+            let input: [u8; 4] = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input, &mut expected);
+            assert_eq!(expected, encode_const::<{ encoded_len(4) }>(&input));
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_encode_const_panic() {
+        let _output: [u8; 7] = encode_const(b"hello");
+    }
+
     #[test]
     fn test_len_divisible_by_5() {
         arbtest(|u| {
@@ -225,7 +611,8 @@ mod tests {
                 input.push(*u.choose(&CHARS)?);
             }
             let mut decoded: Vec<u8> = Vec::with_capacity(output_len);
-            decode(input.as_slice(), &mut decoded).unwrap();
+            let result = decode(input.as_slice(), &mut decoded);
+            assert_eq!(is_valid(&input), result.is_ok(), "input = {input:?}");
             Ok(())
         });
     }
@@ -287,9 +674,10 @@ mod tests {
         arbtest(|u| {
             let hash_len: usize = u.arbitrary_len::<u8>()?;
             let mut hashes = [vec![0_u8; hash_len], vec![0_u8; hash_len]];
-            for i in 0..hash_len {
-                hashes[0][i] = u.arbitrary()?;
-                hashes[1][i] = u.arbitrary()?;
+            let [a, b] = &mut hashes;
+            for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+                *x = u.arbitrary()?;
+                *y = u.arbitrary()?;
             }
             let expected = hashes[0].cmp(&hashes[1]);
             let mut encoded = [
@@ -311,4 +699,36 @@ mod tests {
             Ok(())
         });
     }
+
+    #[test]
+    fn test_encode_with() {
+        check_encode_with::<Crockford>();
+        check_encode_with::<Rfc4648>();
+        check_encode_with::<Base32Hex>();
+        check_encode_with::<ZBase32>();
+    }
+
+    fn check_encode_with<A: Alphabet>() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input, &mut expected);
+            for ch in expected.iter_mut() {
+                *ch = A::CHARS[char_index(*ch) as usize];
+            }
+            let mut actual: Vec<u8> = Vec::new();
+            encode_with::<A, _, _>(&input, &mut actual);
+            assert_eq!(expected, actual);
+            let mut bytes: Vec<u8> = Vec::new();
+            crate::decode_with::<A, _, _>(&actual, &mut bytes).unwrap();
+            assert_eq!(input, bytes);
+            if is_order_preserving::<A>() {
+                let other: Vec<u8> = u.arbitrary()?;
+                let mut other_chars: Vec<u8> = Vec::new();
+                encode_with::<A, _, _>(&other, &mut other_chars);
+                assert_eq!(input.cmp(&other), actual.cmp(&other_chars));
+            }
+            Ok(())
+        });
+    }
 }
@@ -1,15 +1,251 @@
 /// BASE32 decode error.
 ///
-/// Either the length is wrong or some characters are invalid.
-#[derive(Debug)]
-pub struct DecodeError;
+/// Either the length is wrong, some characters are invalid or the unused bits of the last
+/// character are not zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecodeError {
+    kind: DecodeErrorKind,
+    index: usize,
+    code_unit: u16,
+}
+
+impl DecodeError {
+    /// Create new error for the input of the specified length.
+    #[inline]
+    pub(crate) const fn invalid_length(input_len: usize) -> Self {
+        Self {
+            kind: DecodeErrorKind::InvalidLength,
+            index: input_len,
+            code_unit: 0,
+        }
+    }
+
+    /// Create new error for the input of the specified length that decodes to `actual` bytes
+    /// instead of `expected`.
+    #[inline]
+    pub(crate) const fn length_mismatch(input_len: usize, expected: usize, actual: usize) -> Self {
+        Self {
+            kind: DecodeErrorKind::LengthMismatch { expected, actual },
+            index: input_len,
+            code_unit: 0,
+        }
+    }
+
+    /// Create new error for the invalid character at the specified position.
+    #[inline]
+    pub(crate) const fn invalid_char(index: usize, ch: u8) -> Self {
+        Self {
+            kind: DecodeErrorKind::InvalidChar,
+            index,
+            code_unit: ch as u16,
+        }
+    }
+
+    /// Create new error for the last character that has non-zero unused bits.
+    #[inline]
+    pub(crate) const fn non_canonical(index: usize, ch: u8) -> Self {
+        Self {
+            kind: DecodeErrorKind::NonCanonical,
+            index,
+            code_unit: ch as u16,
+        }
+    }
+
+    /// Get the kind of the error.
+    #[inline]
+    pub const fn kind(&self) -> DecodeErrorKind {
+        self.kind
+    }
+
+    /// Get the position of the offending character in the input.
+    ///
+    /// For [`InvalidLength`](DecodeErrorKind::InvalidLength) and
+    /// [`LengthMismatch`](DecodeErrorKind::LengthMismatch) errors this is the length of the input.
+    #[inline]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the offending character.
+    ///
+    /// This is either a byte or a UTF-16 code unit depending on the input (e.g.
+    /// [`PathBufInput`](crate::PathBufInput) produces the latter on Windows).
+    ///
+    /// Returns `None` for [`InvalidLength`](DecodeErrorKind::InvalidLength) and
+    /// [`LengthMismatch`](DecodeErrorKind::LengthMismatch) errors.
+    #[inline]
+    pub const fn code_unit(&self) -> Option<u16> {
+        match self.kind {
+            DecodeErrorKind::InvalidLength | DecodeErrorKind::LengthMismatch { .. } => None,
+            _ => Some(self.code_unit),
+        }
+    }
 
-#[cfg(feature = "std")]
-#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
-impl std::error::Error for DecodeError {}
+    /// Replace the position of the error.
+    ///
+    /// Useful for [`Input`](crate::Input) adapters that need to report the position in the
+    /// original input.
+    #[inline]
+    pub const fn with_index(mut self, index: usize) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Replace the offending character.
+    ///
+    /// Has no effect on [`InvalidLength`](DecodeErrorKind::InvalidLength) and
+    /// [`LengthMismatch`](DecodeErrorKind::LengthMismatch) errors.
+    #[inline]
+    pub const fn with_code_unit(mut self, code_unit: u16) -> Self {
+        if !matches!(
+            self.kind,
+            DecodeErrorKind::InvalidLength | DecodeErrorKind::LengthMismatch { .. }
+        ) {
+            self.code_unit = code_unit;
+        }
+        self
+    }
+}
+
+impl core::error::Error for DecodeError {}
 
 impl core::fmt::Display for DecodeError {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-        f.write_str("BASE32 decode error")
+        match self.kind {
+            DecodeErrorKind::InvalidLength => write!(
+                f,
+                "Invalid BASE32 string length {}: 1, 3 or 6 modulo 8",
+                self.index
+            ),
+            DecodeErrorKind::LengthMismatch { expected, actual } => write!(
+                f,
+                "BASE32 string of length {} decodes to {} bytes, expected {}",
+                self.index, actual, expected
+            ),
+            DecodeErrorKind::InvalidChar => {
+                write!(f, "Invalid BASE32 character ")?;
+                write_code_unit(f, self.code_unit)?;
+                write!(f, " at index {}", self.index)
+            }
+            DecodeErrorKind::NonCanonical => {
+                write!(f, "Non-canonical BASE32 character ")?;
+                write_code_unit(f, self.code_unit)?;
+                write!(f, " at index {}: unused bits are not zero", self.index)
+            }
+        }
+    }
+}
+
+fn write_code_unit(f: &mut core::fmt::Formatter, code_unit: u16) -> core::fmt::Result {
+    match u8::try_from(code_unit) {
+        Ok(ch) if ch.is_ascii() => write!(f, "{:?}", char::from(ch)),
+        // Either a byte or a UTF-16 code unit.
+        _ => write!(f, "0x{code_unit:02x}"),
+    }
+}
+
+/// The kind of [`DecodeError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DecodeErrorKind {
+    /// The length of the input is 1, 3 or 6 modulo 8.
+    InvalidLength,
+    /// The input is well-formed but the length of the decoded byte sequence doesn't match the
+    /// expected length, e.g. when decoding into a fixed-size array.
+    LengthMismatch {
+        /// The expected number of decoded bytes.
+        expected: usize,
+        /// The actual number of decoded bytes.
+        actual: usize,
+    },
+    /// The input contains a character that is not in the alphabet.
+    InvalidChar,
+    /// The unused bits of the last character are not zero.
+    NonCanonical,
+}
+
+/// The output is too small.
+///
+/// Returned by [`try_encode`](crate::try_encode) and [`try_decode`](crate::try_decode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OutputFull {
+    available: usize,
+    required: usize,
+}
+
+impl OutputFull {
+    /// Create new error.
+    ///
+    /// - `available` is the number of bytes that fit into the output.
+    /// - `required` is the number of bytes that were requested.
+    #[inline]
+    pub const fn new(available: usize, required: usize) -> Self {
+        Self {
+            available,
+            required,
+        }
+    }
+
+    /// Get the number of bytes that fit into the output.
+    #[inline]
+    pub const fn available(&self) -> usize {
+        self.available
+    }
+
+    /// Get the number of bytes that were requested.
+    #[inline]
+    pub const fn required(&self) -> usize {
+        self.required
+    }
+}
+
+impl core::error::Error for OutputFull {}
+
+impl core::fmt::Display for OutputFull {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "Output is full: {} bytes required, {} bytes available",
+            self.required, self.available
+        )
+    }
+}
+
+/// An error returned by [`try_decode`](crate::try_decode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TryDecodeError {
+    /// The input is invalid.
+    Decode(DecodeError),
+    /// The output is too small.
+    OutputFull(OutputFull),
+}
+
+impl From<DecodeError> for TryDecodeError {
+    fn from(other: DecodeError) -> Self {
+        Self::Decode(other)
+    }
+}
+
+impl From<OutputFull> for TryDecodeError {
+    fn from(other: OutputFull) -> Self {
+        Self::OutputFull(other)
+    }
+}
+
+impl core::error::Error for TryDecodeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Decode(e) => Some(e),
+            Self::OutputFull(e) => Some(e),
+        }
+    }
+}
+
+impl core::fmt::Display for TryDecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Decode(e) => e.fmt(f),
+            Self::OutputFull(e) => e.fmt(f),
+        }
     }
 }
@@ -0,0 +1,412 @@
+use crate::DecodeError;
+use crate::DecodeErrorKind;
+use crate::EncodeOutput;
+use crate::Input;
+use crate::IntoInput;
+use crate::OutputFull;
+use crate::TryEncodeOutput;
+
+/// An [`EncodeOutput`] adapter that inserts a separator every `group_len` characters.
+///
+/// Makes long hashes easier to read aloud and compare on screen. Use [`GroupedInput`] to decode
+/// the result.
+///
+/// ```rust
+/// use base32_fs::{encode, GroupedOutput};
+///
+/// let mut output = String::new();
+/// encode(b"hello", &mut GroupedOutput::new(&mut output, 4, b'-'));
+/// assert_eq!("d1jp-rv3f", output);
+/// ```
+pub struct GroupedOutput<'a, O: ?Sized> {
+    inner: SeparatedOutput<'a, O, [u8; 1]>,
+}
+
+impl<'a, O: EncodeOutput + ?Sized> GroupedOutput<'a, O> {
+    /// Create new output that writes to `output` and inserts `separator` every `group_len`
+    /// characters.
+    ///
+    /// Panics if `group_len` is zero or if `separator` is not ASCII.
+    pub const fn new(output: &'a mut O, group_len: usize, separator: u8) -> Self {
+        assert!(group_len != 0, "The group length is zero");
+        assert!(separator.is_ascii(), "The separator is not ASCII");
+        Self {
+            inner: SeparatedOutput::new(output, group_len, [separator]),
+        }
+    }
+}
+
+impl<O: EncodeOutput + ?Sized> EncodeOutput for GroupedOutput<'_, O> {
+    #[inline]
+    fn push_chars(&mut self, chars: &[u8]) {
+        self.inner.push_chars(chars);
+    }
+}
+
+impl<O: TryEncodeOutput + ?Sized> TryEncodeOutput for GroupedOutput<'_, O> {
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull> {
+        self.inner.try_reserve(additional)
+    }
+}
+
+/// An [`Input`] adapter that skips the separators inserted by [`GroupedOutput`].
+///
+/// The errors report the positions in the original input including the separators. Length errors
+/// report the position after the last character that is not a separator.
+///
+/// ```rust
+/// use base32_fs::{decode, GroupedInput};
+///
+/// let mut output: Vec<u8> = Vec::new();
+/// decode(GroupedInput::new(b"d1jp-rv3f", b'-'), &mut output).unwrap();
+/// assert_eq!(b"hello", output.as_slice());
+/// ```
+#[derive(Clone)]
+pub struct GroupedInput<I> {
+    inner: SkipInput<I>,
+    separator: u8,
+}
+
+impl<I: Input<8>> GroupedInput<I> {
+    /// Create new input that reads from `input` and skips `separator` characters.
+    pub fn new<T: IntoInput<8, Input = I>>(input: T, separator: u8) -> Self {
+        Self {
+            inner: SkipInput::new(input.into_input()),
+            separator,
+        }
+    }
+}
+
+impl<I: Input<8>> Input<8> for GroupedInput<I> {
+    fn next_chunk(&mut self) -> Option<&[u8]> {
+        let separator = self.separator;
+        self.inner.next_chunk(|ch| (ch != separator).then_some(ch))
+    }
+
+    fn remainder(&self) -> &[u8] {
+        self.inner.remainder()
+    }
+
+    fn map_error(&self, error: DecodeError) -> DecodeError {
+        self.inner.map_error(error)
+    }
+}
+
+/// Output that inserts a separator every `group_len` characters.
+///
+/// The separator is inserted between the groups, i.e. the last group is not terminated.
+pub(crate) struct SeparatedOutput<'a, O: ?Sized, S> {
+    output: &'a mut O,
+    group_len: usize,
+    separator: S,
+    // The number of characters in the current group.
+    len: usize,
+}
+
+impl<'a, O: EncodeOutput + ?Sized, S: AsRef<[u8]>> SeparatedOutput<'a, O, S> {
+    /// Create new output.
+    ///
+    /// `group_len` must not be zero, and `separator` must be ASCII.
+    pub(crate) const fn new(output: &'a mut O, group_len: usize, separator: S) -> Self {
+        Self {
+            output,
+            group_len,
+            separator,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of separators that will be inserted when `additional` characters are
+    /// pushed.
+    const fn num_separators(&self, additional: usize) -> usize {
+        if additional == 0 {
+            return 0;
+        }
+        // The current group contains `len` characters, and the separator precedes every
+        // subsequent group.
+        (self.len + additional - 1) / self.group_len
+    }
+}
+
+impl<O: EncodeOutput + ?Sized, S: AsRef<[u8]>> EncodeOutput for SeparatedOutput<'_, O, S> {
+    #[inline]
+    fn push_chars(&mut self, mut chars: &[u8]) {
+        while !chars.is_empty() {
+            if self.len == self.group_len {
+                self.output.push_chars(self.separator.as_ref());
+                self.len = 0;
+            }
+            let n = (self.group_len - self.len).min(chars.len());
+            let (group, rest) = chars.split_at(n);
+            self.output.push_chars(group);
+            self.len += n;
+            chars = rest;
+        }
+    }
+}
+
+impl<O: TryEncodeOutput + ?Sized, S: AsRef<[u8]>> TryEncodeOutput for SeparatedOutput<'_, O, S> {
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull> {
+        let separators_len = self.num_separators(additional) * self.separator.as_ref().len();
+        self.output.try_reserve(additional + separators_len)
+    }
+}
+
+/// Input that skips some characters of the inner input and remembers the positions and the
+/// original values of the others.
+#[derive(Clone)]
+pub(crate) struct SkipInput<I> {
+    input: I,
+    // The bytes of the inner input that were not consumed yet.
+    raw: [u8; 8],
+    raw_pos: usize,
+    raw_len: usize,
+    // Whether the remainder of the inner input was consumed.
+    exhausted: bool,
+    // The position of the next character in the input.
+    pos: usize,
+    // The position after the last character that was not skipped.
+    end: usize,
+    chunk: [u8; 8],
+    // The positions and the original values of the chunk's characters.
+    positions: [usize; 8],
+    originals: [u8; 8],
+    len: usize,
+    // The position of the chunk in the input without the skipped characters.
+    offset: usize,
+}
+
+impl<I: Input<8>> SkipInput<I> {
+    pub(crate) const fn new(input: I) -> Self {
+        Self {
+            input,
+            raw: [0; 8],
+            raw_pos: 0,
+            raw_len: 0,
+            exhausted: false,
+            pos: 0,
+            end: 0,
+            chunk: [0; 8],
+            positions: [0; 8],
+            originals: [0; 8],
+            len: 0,
+            offset: 0,
+        }
+    }
+
+    /// Get the next byte of the inner input.
+    #[inline]
+    fn next_byte(&mut self) -> Option<u8> {
+        if self.raw_pos == self.raw_len {
+            if self.exhausted {
+                return None;
+            }
+            let bytes = match self.input.next_chunk() {
+                Some(chunk) => chunk,
+                None => {
+                    self.exhausted = true;
+                    self.input.remainder()
+                }
+            };
+            self.raw[..bytes.len()].copy_from_slice(bytes);
+            self.raw_pos = 0;
+            self.raw_len = bytes.len();
+            if self.raw_len == 0 {
+                return None;
+            }
+        }
+        let byte = self.raw[self.raw_pos];
+        self.raw_pos += 1;
+        Some(byte)
+    }
+
+    /// Get the next chunk replacing each character with the one returned by `map`.
+    ///
+    /// The characters for which `map` returns `None` are skipped.
+    #[inline]
+    pub(crate) fn next_chunk(&mut self, mut map: impl FnMut(u8) -> Option<u8>) -> Option<&[u8]> {
+        self.offset += self.len;
+        self.len = 0;
+        while self.len < 8 {
+            let original = self.next_byte()?;
+            let position = self.pos;
+            self.pos += 1;
+            let Some(ch) = map(original) else {
+                continue;
+            };
+            self.chunk[self.len] = ch;
+            self.positions[self.len] = position;
+            self.originals[self.len] = original;
+            self.len += 1;
+            self.end = self.pos;
+        }
+        Some(&self.chunk[..])
+    }
+
+    pub(crate) fn remainder(&self) -> &[u8] {
+        &self.chunk[..self.len]
+    }
+
+    /// Report the positions and the characters of the original input.
+    ///
+    /// The length errors report the position after the last character that was not skipped.
+    pub(crate) fn map_error(&self, error: DecodeError) -> DecodeError {
+        if matches!(
+            error.kind(),
+            DecodeErrorKind::InvalidLength | DecodeErrorKind::LengthMismatch { .. }
+        ) {
+            return error.with_index(self.end);
+        }
+        match error.index().checked_sub(self.offset) {
+            Some(i) if i < self.len => error
+                .with_index(self.positions[i])
+                .with_code_unit(self.originals[i].into()),
+            _ => error,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use arbitrary::Unstructured;
+    use arbtest::arbtest;
+
+    use crate::decode;
+    use crate::encode;
+    use crate::encode_to_string;
+    use crate::try_encode;
+
+    /// Checks the output adapter that inserts `separator` every `group_len` characters and the
+    /// input adapter that skips it.
+    ///
+    /// `with_output` calls the supplied function with the output adapter that wraps the supplied
+    /// output, `decode_from` decodes the string using the input adapter.
+    pub(crate) fn check_separated(
+        u: &mut Unstructured<'_>,
+        separator: &[u8],
+        with_output: impl Fn(&mut dyn TryEncodeOutput, usize, &mut dyn FnMut(&mut dyn TryEncodeOutput)),
+        decode_from: impl Fn(&[u8], &mut Vec<u8>) -> Result<(), DecodeError>,
+    ) -> arbitrary::Result<()> {
+        let input: Vec<u8> = u.arbitrary()?;
+        let group_len: usize = u.int_in_range(1..=80)?;
+        let chars = encode_to_string(&input);
+        let expected = chars.as_bytes().chunks(group_len).collect::<Vec<_>>();
+        let expected = expected.join(separator);
+        let mut actual: Vec<u8> = Vec::new();
+        with_output(&mut actual, group_len, &mut |output| encode(&input, output));
+        assert_eq!(expected, actual);
+        let mut string = String::new();
+        with_output(&mut string, group_len, &mut |output| encode(&input, output));
+        assert_eq!(expected, string.as_bytes());
+        // Push the characters in random pieces.
+        let mut pieces = Vec::new();
+        let mut rest = chars.as_bytes();
+        while !rest.is_empty() {
+            let (piece, tail) = rest.split_at(u.choose_index(rest.len())? + 1);
+            pieces.push(piece);
+            rest = tail;
+        }
+        let mut actual: Vec<u8> = Vec::new();
+        with_output(&mut actual, group_len, &mut |output| {
+            for piece in pieces.iter() {
+                output.push_chars(piece);
+            }
+        });
+        assert_eq!(expected, actual);
+        let mut buf = vec![0_u8; expected.len()];
+        let mut slice = &mut buf[..];
+        with_output(&mut slice, group_len, &mut |output| {
+            assert_eq!(Ok(()), try_encode(&input, output));
+        });
+        assert_eq!(expected, buf);
+        if !expected.is_empty() {
+            let mut slice = &mut buf[1..];
+            with_output(&mut slice, group_len, &mut |output| {
+                assert!(try_encode(&input, output).is_err());
+            });
+        }
+        let mut bytes: Vec<u8> = Vec::new();
+        decode_from(&expected, &mut bytes).unwrap();
+        assert_eq!(input, bytes);
+        Ok(())
+    }
+
+    /// Checks that the input adapter that skips the characters for which `skip` returns `true`
+    /// reports the same errors as the canonical input, but with the original positions.
+    pub(crate) fn check_skip_errors(
+        u: &mut Unstructured<'_>,
+        skip: impl Fn(u8) -> bool,
+        decode_from: impl Fn(&[u8], &mut Vec<u8>) -> Result<(), DecodeError>,
+    ) -> arbitrary::Result<()> {
+        let input: Vec<u8> = u.arbitrary()?;
+        let stripped: Vec<u8> = input.iter().copied().filter(|ch| !skip(*ch)).collect();
+        let mut expected: Vec<u8> = Vec::new();
+        let expected_result = decode(&stripped, &mut expected);
+        let mut actual: Vec<u8> = Vec::new();
+        let actual_result = decode_from(&input, &mut actual);
+        assert_eq!(expected_result.is_ok(), actual_result.is_ok());
+        if let Err(e) = actual_result {
+            match e.kind() {
+                DecodeErrorKind::InvalidLength => {
+                    let end = input.iter().rposition(|ch| !skip(*ch)).map_or(0, |i| i + 1);
+                    assert_eq!(end, e.index());
+                }
+                _ => assert_eq!(e.code_unit(), Some(input[e.index()].into())),
+            }
+            assert_eq!(expected_result.unwrap_err().kind(), e.kind());
+        } else {
+            assert_eq!(expected, actual);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_grouped() {
+        arbtest(|u| {
+            let separator: u8 = *u.choose(b"-_ ")?;
+            check_separated(
+                u,
+                &[separator],
+                |output, group_len, f| f(&mut GroupedOutput::new(output, group_len, separator)),
+                |input, output| decode(GroupedInput::new(input, separator), output),
+            )
+        });
+    }
+
+    #[test]
+    fn test_grouped_errors() {
+        arbtest(|u| {
+            check_skip_errors(
+                u,
+                |ch| ch == b'-',
+                |input, output| decode(GroupedInput::new(input, b'-'), output),
+            )
+        });
+    }
+
+    #[test]
+    fn test_error_index() {
+        let mut output: Vec<u8> = Vec::new();
+        let e = decode(GroupedInput::new(b"d1jp-rv3i", b'-'), &mut output).unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidChar, e.kind());
+        assert_eq!(8, e.index());
+        let e = decode(
+            GroupedInput::new([&b"d1j"[..], b"p-rv", b"3i"], b'-'),
+            &mut output,
+        )
+        .unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidChar, e.kind());
+        assert_eq!(8, e.index());
+        assert_eq!(Some(u16::from(b'i')), e.code_unit());
+        let e = decode(GroupedInput::new("d1jp-rv3f-01", b'-'), &mut output).unwrap_err();
+        assert_eq!(DecodeErrorKind::NonCanonical, e.kind());
+        assert_eq!(11, e.index());
+        let e = decode(GroupedInput::new(b"d1jp-rv3f-0-", b'-'), &mut output).unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidLength, e.kind());
+        assert_eq!(11, e.index());
+    }
+}
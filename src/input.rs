@@ -1,14 +1,35 @@
-/// An input for [`decode`](crate::decode).
+use crate::DecodeError;
+
+/// An input for [`encode`](crate::encode) (`N = 5`) or [`decode`](crate::decode) (`N = 8`).
 pub trait Input<const N: usize> {
     /// Get the next chunk of size `N` from the input.
     ///
-    /// Returns `None` if the input doesn't have sufficient number of bytes.
+    /// Returns `None` if the input doesn't have sufficient number of bytes. The chunk must contain
+    /// exactly `N` bytes, otherwise [`encode`](crate::encode) panics.
     fn next_chunk(&mut self) -> Option<&[u8]>;
 
+    /// Get the next `count` chunks of size `N` as one contiguous slice.
+    ///
+    /// Allows [`decode`](crate::decode) to process several chunks at once.
+    ///
+    /// Returns `None` if the input doesn't have sufficient number of bytes or if it can't return
+    /// contiguous slices. The default implementation always returns `None`.
+    fn next_chunks(&mut self, _count: usize) -> Option<&[u8]> {
+        None
+    }
+
     /// Get the remainder of the input.
     ///
     /// Should only be called after [`next_chunk`](Self::next_chunk) returns `None`.
     fn remainder(&self) -> &[u8];
+
+    /// Adjust the error that occurred while decoding the last chunk or the remainder.
+    ///
+    /// Inputs that transform the original characters override this method to report the original
+    /// character and its position. The default implementation returns the error as is.
+    fn map_error(&self, error: DecodeError) -> DecodeError {
+        error
+    }
 }
 
 impl<const N: usize> Input<N> for &[u8] {
@@ -18,7 +39,434 @@ impl<const N: usize> Input<N> for &[u8] {
         Some(chunk)
     }
 
+    fn next_chunks(&mut self, count: usize) -> Option<&[u8]> {
+        let (chunks, rest) = self.split_at_checked(N.checked_mul(count)?)?;
+        *self = rest;
+        Some(chunks)
+    }
+
+    fn remainder(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<const N: usize, I: Input<N> + ?Sized> Input<N> for &mut I {
+    fn next_chunk(&mut self) -> Option<&[u8]> {
+        (**self).next_chunk()
+    }
+
+    fn next_chunks(&mut self, count: usize) -> Option<&[u8]> {
+        (**self).next_chunks(count)
+    }
+
+    fn remainder(&self) -> &[u8] {
+        (**self).remainder()
+    }
+
+    fn map_error(&self, error: DecodeError) -> DecodeError {
+        (**self).map_error(error)
+    }
+}
+
+/// Conversion into [`Input`].
+///
+/// [`encode`](crate::encode) and [`decode`](crate::decode) accept any type that implements this
+/// trait: inputs themselves, byte arrays, vectors, string slices, as well as tuples and arrays of
+/// byte slices. The latter are processed as if they were concatenated.
+///
+/// ```rust
+/// use base32_fs::encode_to_string;
+/// use base32_fs::encode;
+///
+/// let tag = b"hel";
+/// let digest = b"lo";
+/// let mut output = String::new();
+/// encode((&tag[..], &digest[..]), &mut output);
+/// assert_eq!(encode_to_string(b"hello"), output);
+/// ```
+pub trait IntoInput<const N: usize> {
+    /// The input type.
+    type Input: Input<N>;
+
+    /// Convert into input.
+    fn into_input(self) -> Self::Input;
+}
+
+impl<const N: usize, I: Input<N>> IntoInput<N> for I {
+    type Input = I;
+
+    #[inline]
+    fn into_input(self) -> Self::Input {
+        self
+    }
+}
+
+impl<'a, const N: usize, const M: usize> IntoInput<N> for &'a [u8; M] {
+    type Input = &'a [u8];
+
+    #[inline]
+    fn into_input(self) -> Self::Input {
+        &self[..]
+    }
+}
+
+impl<'a, const N: usize> IntoInput<N> for &'a str {
+    type Input = &'a [u8];
+
+    #[inline]
+    fn into_input(self) -> Self::Input {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<'a, const N: usize> IntoInput<N> for &'a alloc::vec::Vec<u8> {
+    type Input = &'a [u8];
+
+    #[inline]
+    fn into_input(self) -> Self::Input {
+        &self[..]
+    }
+}
+
+impl<const N: usize> IntoInput<N> for (&[u8], &[u8]) {
+    type Input = SourceInput<Self, N>;
+
+    #[inline]
+    fn into_input(self) -> Self::Input {
+        SourceInput::new(self)
+    }
+}
+
+impl<const N: usize, const M: usize> IntoInput<N> for [&[u8]; M] {
+    type Input = SourceInput<Self, N>;
+
+    #[inline]
+    fn into_input(self) -> Self::Input {
+        SourceInput::new(self)
+    }
+}
+
+/// An implementation of [`Input`] for iterators over bytes.
+///
+/// ```rust
+/// use base32_fs::{decode, IterInput};
+///
+/// let input = IterInput::new("d1jprv3f".bytes());
+/// let mut output: Vec<u8> = Vec::new();
+/// decode(input, &mut output).unwrap();
+/// assert_eq!(b"hello", output.as_slice());
+/// ```
+#[derive(Clone)]
+pub struct IterInput<I, const N: usize> {
+    iter: I,
+    chunk: [u8; N],
+    len: usize,
+}
+
+impl<I: Iterator<Item = u8>, const N: usize> IterInput<I, N> {
+    /// Create new input from the supplied iterator.
+    pub const fn new(iter: I) -> Self {
+        Self {
+            iter,
+            chunk: [0; N],
+            len: 0,
+        }
+    }
+}
+
+impl<I: Iterator<Item = u8>, const N: usize> Input<N> for IterInput<I, N> {
+    fn next_chunk(&mut self) -> Option<&[u8]> {
+        self.len = 0;
+        for byte in self.chunk.iter_mut() {
+            *byte = self.iter.next()?;
+            self.len += 1;
+        }
+        Some(&self.chunk[..])
+    }
+
     fn remainder(&self) -> &[u8] {
+        &self.chunk[..self.len]
+    }
+}
+
+/// A source of bytes that are stored in one or more contiguous buffers.
+///
+/// This trait is similar to `bytes::Buf`. Use [`SourceInput`] to decode the bytes.
+pub trait ByteSource {
+    /// Get the next contiguous buffer.
+    ///
+    /// Returns an empty slice if there are no more bytes.
+    fn chunk(&mut self) -> &[u8];
+
+    /// Consume `n` bytes of the current buffer.
+    ///
+    /// `n` is never greater than the length of the slice returned by [`chunk`](Self::chunk).
+    fn advance(&mut self, n: usize);
+}
+
+impl ByteSource for &[u8] {
+    fn chunk(&mut self) -> &[u8] {
         self
     }
+
+    fn advance(&mut self, n: usize) {
+        *self = &self[n..];
+    }
+}
+
+/// Chain of two sources.
+impl<A: ByteSource, B: ByteSource> ByteSource for (A, B) {
+    fn chunk(&mut self) -> &[u8] {
+        if !self.0.chunk().is_empty() {
+            return self.0.chunk();
+        }
+        self.1.chunk()
+    }
+
+    fn advance(&mut self, n: usize) {
+        if !self.0.chunk().is_empty() {
+            self.0.advance(n);
+        } else {
+            self.1.advance(n);
+        }
+    }
+}
+
+/// Concatenation of the slices.
+impl<const M: usize> ByteSource for [&[u8]; M] {
+    fn chunk(&mut self) -> &[u8] {
+        match self.iter().find(|slice| !slice.is_empty()) {
+            Some(slice) => slice,
+            None => &[],
+        }
+    }
+
+    fn advance(&mut self, n: usize) {
+        if let Some(slice) = self.iter_mut().find(|slice| !slice.is_empty()) {
+            *slice = &slice[n..];
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl ByteSource for alloc::collections::VecDeque<u8> {
+    fn chunk(&mut self) -> &[u8] {
+        self.as_slices().0
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.drain(..n);
+    }
+}
+
+impl<S: ByteSource + ?Sized> ByteSource for &mut S {
+    fn chunk(&mut self) -> &[u8] {
+        (**self).chunk()
+    }
+
+    fn advance(&mut self, n: usize) {
+        (**self).advance(n)
+    }
+}
+
+/// An implementation of [`ByteSource`] for [`BufRead`](std::io::BufRead).
+///
+/// The first I/O error ends the input and is stored to be inspected after decoding.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct BufReadSource<R> {
+    reader: R,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead> BufReadSource<R> {
+    /// Create new source from the supplied reader.
+    pub const fn new(reader: R) -> Self {
+        Self {
+            reader,
+            error: None,
+        }
+    }
+
+    /// Returns the I/O error that ended the input, if any.
+    pub fn take_error(&mut self) -> Option<std::io::Error> {
+        self.error.take()
+    }
+
+    /// Transform into the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead> ByteSource for BufReadSource<R> {
+    fn chunk(&mut self) -> &[u8] {
+        if self.error.is_some() {
+            return &[];
+        }
+        loop {
+            match self.reader.fill_buf() {
+                Ok(_) => break,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.error = Some(e);
+                    return &[];
+                }
+            }
+        }
+        // The buffer is already filled, i.e. this call doesn't fail.
+        self.reader.fill_buf().unwrap_or_default()
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.reader.consume(n);
+    }
+}
+
+/// An implementation of [`Input`] for any [`ByteSource`].
+///
+/// Chunks that are contiguous in the source are returned without copying.
+///
+/// ```rust
+/// use base32_fs::{decode, SourceInput};
+/// use std::collections::VecDeque;
+///
+/// let input = SourceInput::new((&b"d1jp"[..], &b"rv3f"[..]));
+/// let mut output: Vec<u8> = Vec::new();
+/// decode(input, &mut output).unwrap();
+/// assert_eq!(b"hello", output.as_slice());
+///
+/// let mut deque: VecDeque<u8> = b"d1jprv3f".iter().copied().collect();
+/// let mut output: Vec<u8> = Vec::new();
+/// decode(SourceInput::new(&mut deque), &mut output).unwrap();
+/// assert_eq!(b"hello", output.as_slice());
+/// ```
+#[derive(Clone)]
+pub struct SourceInput<S, const N: usize> {
+    source: S,
+    chunk: [u8; N],
+    len: usize,
+    // The number of bytes that are borrowed from the source by the last call to `next_chunk`.
+    borrowed: usize,
+}
+
+impl<S: ByteSource, const N: usize> SourceInput<S, N> {
+    /// Create new input from the supplied source.
+    pub const fn new(source: S) -> Self {
+        Self {
+            source,
+            chunk: [0; N],
+            len: 0,
+            borrowed: 0,
+        }
+    }
+
+    /// Transform into the underlying source.
+    pub fn into_inner(mut self) -> S {
+        self.source.advance(core::mem::take(&mut self.borrowed));
+        self.source
+    }
+}
+
+impl<S: ByteSource, const N: usize> Input<N> for SourceInput<S, N> {
+    fn next_chunk(&mut self) -> Option<&[u8]> {
+        self.source.advance(core::mem::take(&mut self.borrowed));
+        self.len = 0;
+        if self.source.chunk().len() >= N {
+            // Fast path: borrow the chunk from the source.
+            self.borrowed = N;
+            return Some(&self.source.chunk()[..N]);
+        }
+        while self.len != N {
+            let chunk = self.source.chunk();
+            if chunk.is_empty() {
+                return None;
+            }
+            let n = chunk.len().min(N - self.len);
+            self.chunk[self.len..][..n].copy_from_slice(&chunk[..n]);
+            self.source.advance(n);
+            self.len += n;
+        }
+        self.len = 0;
+        Some(&self.chunk[..])
+    }
+
+    fn next_chunks(&mut self, count: usize) -> Option<&[u8]> {
+        self.source.advance(core::mem::take(&mut self.borrowed));
+        self.len = 0;
+        let len = N.checked_mul(count)?;
+        if self.source.chunk().len() < len {
+            return None;
+        }
+        self.borrowed = len;
+        Some(&self.source.chunk()[..len])
+    }
+
+    fn remainder(&self) -> &[u8] {
+        &self.chunk[..self.len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::VecDeque;
+    use alloc::vec::Vec;
+    use arbtest::arbtest;
+
+    use crate::decode;
+
+    #[test]
+    fn test_inputs() {
+        arbtest(|u| {
+            // Longer than one SIMD block.
+            let len: usize = u.int_in_range(0..=1000)?;
+            let bytes: Vec<u8> = (0..len).map(|_| u.arbitrary()).collect::<Result<_, _>>()?;
+            let mut input: Vec<u8> = Vec::new();
+            crate::encode(&bytes[..], &mut input);
+            if !input.is_empty() && u.arbitrary()? {
+                // Corrupt the input.
+                let i = u.choose_index(input.len())?;
+                input[i] = u.arbitrary()?;
+            }
+            let mut expected: Vec<u8> = Vec::new();
+            let expected_result = decode(&input[..], &mut expected);
+            let check = |actual_result, actual: Vec<u8>| {
+                assert_eq!(expected_result, actual_result);
+                assert_eq!(expected, actual);
+            };
+            let i = u.choose_index(input.len() + 1)?;
+            let (a, b) = input.split_at(i);
+            let mut actual: Vec<u8> = Vec::new();
+            let result = decode(SourceInput::new((a, b)), &mut actual);
+            check(result, actual);
+            let mut deque: VecDeque<u8> = b.iter().copied().collect();
+            for byte in a.iter().rev() {
+                deque.push_front(*byte);
+            }
+            let mut actual: Vec<u8> = Vec::new();
+            let result = decode(SourceInput::new(&mut deque), &mut actual);
+            check(result, actual);
+            let capacity = u.int_in_range(1..=100)?;
+            let reader = std::io::BufReader::with_capacity(capacity, &input[..]);
+            let mut source = BufReadSource::new(reader);
+            let mut actual: Vec<u8> = Vec::new();
+            let result = decode(SourceInput::new(&mut source), &mut actual);
+            assert!(source.take_error().is_none());
+            check(result, actual);
+            let mut iter_input = IterInput::new(input.iter().copied());
+            let mut actual: Vec<u8> = Vec::new();
+            let result = decode(&mut iter_input, &mut actual);
+            if result.is_ok() {
+                assert_eq!(input.len() % 8, Input::<8>::remainder(&iter_input).len());
+            }
+            check(result, actual);
+            Ok(())
+        });
+    }
 }
@@ -0,0 +1,519 @@
+use std::io::Read;
+use std::io::Write;
+
+use crate::decode;
+use crate::encode;
+use crate::encoded_len;
+use crate::DecodeError;
+
+/// The number of bytes that are encoded at once.
+const BATCH_LEN: usize = 64 * 5;
+
+/// Streaming encoder that writes BASE32-encoded string to the underlying [`Write`].
+///
+/// Incomplete 5-byte groups are buffered across [`write`](Write::write) calls, and so are the
+/// encoded characters: they are written to the underlying writer by the next
+/// [`write`](Write::write), [`flush`](Write::flush) or [`finish`](Self::finish) call, similar to
+/// [`BufWriter`](std::io::BufWriter). The last group is written by [`finish`](Self::finish). The
+/// resulting string is the same as the one produced by [`encode`](crate::encode).
+///
+/// **Call [`finish`](Self::finish) explicitly.** Dropping the encoder writes the buffered
+/// characters and the last group as well, but the errors are ignored and the underlying writer is
+/// not flushed.
+///
+/// ```rust
+/// use base32_fs::Encoder;
+/// use std::io::Write;
+///
+/// let mut encoder = Encoder::new(Vec::new());
+/// encoder.write_all(b"hel").unwrap();
+/// encoder.write_all(b"lo").unwrap();
+/// assert_eq!(b"d1jprv3f", encoder.finish().unwrap().as_slice());
+/// ```
+pub struct Encoder<W: Write> {
+    // `None` only after `finish`.
+    writer: Option<W>,
+    // The last incomplete group.
+    pending: [u8; 5],
+    pending_len: usize,
+    // Encoded characters that were not written yet.
+    buf: [u8; encoded_len(BATCH_LEN)],
+    buf_start: usize,
+    buf_end: usize,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Create new encoder that writes to `writer`.
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer: Some(writer),
+            pending: [0_u8; 5],
+            pending_len: 0,
+            buf: [0_u8; encoded_len(BATCH_LEN)],
+            buf_start: 0,
+            buf_end: 0,
+        }
+    }
+
+    /// Get a reference to the underlying writer.
+    pub const fn get_ref(&self) -> &W {
+        match &self.writer {
+            Some(writer) => writer,
+            None => unreachable!(),
+        }
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    ///
+    /// Writing directly to the underlying writer corrupts the encoded string.
+    pub fn get_mut(&mut self) -> &mut W {
+        match &mut self.writer {
+            Some(writer) => writer,
+            None => unreachable!(),
+        }
+    }
+
+    /// Write the buffered characters and the last incomplete group, flush the underlying writer
+    /// and return it.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        self.write_last_group()?;
+        self.get_mut().flush()?;
+        match self.writer.take() {
+            Some(writer) => Ok(writer),
+            None => unreachable!(),
+        }
+    }
+
+    /// Write the buffered characters and the last incomplete group.
+    fn write_last_group(&mut self) -> std::io::Result<()> {
+        self.write_buf()?;
+        let len = encoded_len(self.pending_len);
+        encode(&self.pending[..self.pending_len], &mut &mut self.buf[..len]);
+        self.pending_len = 0;
+        self.buf_start = 0;
+        self.buf_end = len;
+        self.write_buf()
+    }
+
+    /// Write the buffered characters to the underlying writer.
+    ///
+    /// The characters are removed from the buffer as soon as they are written, i.e. on error only
+    /// the characters that were not written remain in the buffer.
+    fn write_buf(&mut self) -> std::io::Result<()> {
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => unreachable!(),
+        };
+        while self.buf_start != self.buf_end {
+            match writer.write(&self.buf[self.buf_start..self.buf_end]) {
+                Ok(0) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::WriteZero,
+                        "failed to write the encoded string",
+                    ))
+                }
+                Ok(n) => self.buf_start += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for Encoder<W> {
+    fn drop(&mut self) {
+        if self.writer.is_some() {
+            // Best effort: the errors can only be handled by calling `finish`.
+            let _ = self.write_last_group();
+        }
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    /// Write the characters that were encoded by the previous call, then encode the complete
+    /// 5-byte groups and buffer the rest.
+    ///
+    /// On error no input is consumed.
+    fn write(&mut self, mut input: &[u8]) -> std::io::Result<usize> {
+        self.write_buf()?;
+        self.buf_start = 0;
+        self.buf_end = 0;
+        let mut consumed = 0;
+        if self.pending_len != 0 {
+            let n = (5 - self.pending_len).min(input.len());
+            let (head, tail) = input.split_at(n);
+            self.pending[self.pending_len..][..n].copy_from_slice(head);
+            self.pending_len += n;
+            consumed += n;
+            input = tail;
+            if self.pending_len != 5 {
+                return Ok(consumed);
+            }
+            encode(&self.pending, &mut &mut self.buf[..8]);
+            self.buf_end += 8;
+            self.pending_len = 0;
+        }
+        let max_len = (self.buf.len() - self.buf_end) / 8 * 5;
+        let n = (input.len() / 5 * 5).min(max_len);
+        let (head, tail) = input.split_at(n);
+        encode(head, &mut &mut self.buf[self.buf_end..][..encoded_len(n)]);
+        self.buf_end += encoded_len(n);
+        consumed += n;
+        if tail.len() < 5 {
+            // Buffer the last incomplete group.
+            self.pending[..tail.len()].copy_from_slice(tail);
+            self.pending_len = tail.len();
+            consumed += tail.len();
+        }
+        Ok(consumed)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.write_buf()?;
+        self.get_mut().flush()
+    }
+}
+
+/// Streaming decoder that reads BASE32-encoded string from the underlying [`Read`].
+///
+/// Complete 8-character groups are decoded as soon as they arrive, the validity of the last
+/// incomplete group is checked at the end of the stream. Decoding errors are reported as
+/// [`InvalidData`](std::io::ErrorKind::InvalidData) errors that wrap [`DecodeError`] with the
+/// index relative to the start of the stream. The bytes decoded before the error are returned
+/// first.
+///
+/// ```rust
+/// use base32_fs::DecodeError;
+/// use base32_fs::Decoder;
+/// use std::io::Read;
+///
+/// let mut output = Vec::new();
+/// Decoder::new(&b"d1jprv3f"[..]).read_to_end(&mut output).unwrap();
+/// assert_eq!(b"hello", output.as_slice());
+///
+/// let e = Decoder::new(&b"d1jprv3i"[..]).read_to_end(&mut output).unwrap_err();
+/// let e = e.get_ref().and_then(|e| e.downcast_ref::<DecodeError>()).unwrap();
+/// assert_eq!(7, e.index());
+/// ```
+pub struct Decoder<R: Read> {
+    reader: R,
+    // Encoded characters.
+    input: [u8; encoded_len(BATCH_LEN)],
+    input_start: usize,
+    input_end: usize,
+    // Decoded bytes.
+    output: [u8; BATCH_LEN],
+    output_start: usize,
+    output_end: usize,
+    // The position of `input_start` in the stream.
+    offset: usize,
+    error: Option<DecodeError>,
+    eof: bool,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Create new decoder that reads from `reader`.
+    pub const fn new(reader: R) -> Self {
+        Self {
+            reader,
+            input: [0_u8; encoded_len(BATCH_LEN)],
+            input_start: 0,
+            input_end: 0,
+            output: [0_u8; BATCH_LEN],
+            output_start: 0,
+            output_end: 0,
+            offset: 0,
+            error: None,
+            eof: false,
+        }
+    }
+
+    /// Get a reference to the underlying reader.
+    pub const fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    ///
+    /// Reading directly from the underlying reader corrupts the decoded byte sequence.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Transform into the underlying reader.
+    ///
+    /// The buffered characters are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Read more characters and decode the complete groups.
+    fn fill(&mut self) -> std::io::Result<()> {
+        // Move the incomplete group to the beginning of the buffer.
+        self.input.copy_within(self.input_start..self.input_end, 0);
+        self.input_end -= self.input_start;
+        self.input_start = 0;
+        let n = loop {
+            match self.reader.read(&mut self.input[self.input_end..]) {
+                Ok(n) => break n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        };
+        self.input_end += n;
+        self.eof = n == 0;
+        // Decode the last incomplete group only at the end of the stream.
+        let len = if self.eof {
+            self.input_end
+        } else {
+            self.input_end / 8 * 8
+        };
+        let mut output = &mut self.output[..];
+        let result = decode(&self.input[..len], &mut output);
+        self.output_start = 0;
+        self.output_end = BATCH_LEN - output.len();
+        if let Err(e) = result {
+            self.error = Some(e.with_index(self.offset + e.index()));
+        }
+        self.input_start = len;
+        self.offset += len;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.output_start == self.output_end {
+            if let Some(e) = self.error {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e));
+            }
+            if self.eof {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+        let output = &self.output[self.output_start..self.output_end];
+        let n = output.len().min(buf.len());
+        buf[..n].copy_from_slice(&output[..n]);
+        self.output_start += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use arbitrary::Unstructured;
+    use arbtest::arbtest;
+
+    /// Writer that writes at most `max_len` bytes at once.
+    struct ShortWriter {
+        bytes: Vec<u8>,
+        max_len: usize,
+    }
+
+    impl Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.max_len);
+            self.bytes.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_encoder() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input[..], &mut expected);
+            let writer = ShortWriter {
+                bytes: Vec::new(),
+                max_len: u.int_in_range(1..=1000)?,
+            };
+            let mut encoder = Encoder::new(writer);
+            for piece in random_split(u, &input)? {
+                encoder.write_all(piece).unwrap();
+            }
+            let actual = encoder.finish().unwrap().bytes;
+            assert_eq!(expected, actual);
+            Ok(())
+        });
+    }
+
+    /// Writer that writes at most `max_len` bytes at once and fails every `period`-th write.
+    struct FailingWriter {
+        bytes: Vec<u8>,
+        max_len: usize,
+        period: usize,
+        num_writes: usize,
+    }
+
+    impl Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.num_writes += 1;
+            if self.num_writes.is_multiple_of(self.period) {
+                return Err(std::io::Error::other("failed"));
+            }
+            let n = buf.len().min(self.max_len);
+            self.bytes.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_encoder_errors() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input[..], &mut expected);
+            let writer = FailingWriter {
+                bytes: Vec::new(),
+                max_len: u.int_in_range(1..=1000)?,
+                period: u.int_in_range(2..=10)?,
+                num_writes: 0,
+            };
+            let mut encoder = Encoder::new(writer);
+            for mut piece in random_split(u, &input)? {
+                // Retry until the whole piece is consumed.
+                while !piece.is_empty() {
+                    if let Ok(n) = encoder.write(piece) {
+                        piece = &piece[n..];
+                    }
+                }
+                if u.arbitrary()? {
+                    // Retry until the buffered characters are written.
+                    while encoder.flush().is_err() {}
+                }
+            }
+            encoder.get_mut().period = usize::MAX;
+            let actual = encoder.finish().unwrap().bytes;
+            assert_eq!(expected, actual);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_encoder_drop() {
+        let mut output: Vec<u8> = Vec::new();
+        let mut encoder = Encoder::new(&mut output);
+        encoder.write_all(b"hel").unwrap();
+        drop(encoder);
+        assert_eq!(b"d1jpr", output.as_slice());
+    }
+
+    #[test]
+    fn test_large_write() {
+        arbtest(|u| {
+            let len: usize = u.int_in_range(0..=10 * BATCH_LEN)?;
+            let input: Vec<u8> = (0..len).map(|_| u.arbitrary()).collect::<Result<_, _>>()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input[..], &mut expected);
+            let mut encoder = Encoder::new(Vec::new());
+            encoder.write_all(&input).unwrap();
+            assert_eq!(expected, encoder.finish().unwrap());
+            Ok(())
+        });
+    }
+
+    /// Reader that reads at most `max_len` bytes at once.
+    struct ShortReader<'a> {
+        bytes: &'a [u8],
+        max_len: usize,
+    }
+
+    impl Read for ShortReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.max_len).min(self.bytes.len());
+            let (head, tail) = self.bytes.split_at(n);
+            buf[..n].copy_from_slice(head);
+            self.bytes = tail;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_decoder() {
+        arbtest(|u| {
+            // Longer than one SIMD block.
+            let len: usize = u.int_in_range(0..=1000)?;
+            let bytes: Vec<u8> = (0..len).map(|_| u.arbitrary()).collect::<Result<_, _>>()?;
+            let mut input: Vec<u8> = Vec::new();
+            encode(&bytes[..], &mut input);
+            if !input.is_empty() && u.arbitrary()? {
+                // Corrupt the input.
+                let i = u.choose_index(input.len())?;
+                input[i] = u.arbitrary()?;
+            }
+            if u.arbitrary()? {
+                // Truncate the input.
+                let len = u.choose_index(input.len() + 1)?;
+                input.truncate(len);
+            }
+            let mut expected: Vec<u8> = Vec::new();
+            let expected_result = crate::decode(&input[..], &mut expected);
+            let reader = ShortReader {
+                bytes: &input[..],
+                max_len: u.int_in_range(1..=1000)?,
+            };
+            let mut actual: Vec<u8> = Vec::new();
+            let mut decoder = Decoder::new(reader);
+            let actual_result = loop {
+                let mut buf = [0_u8; 100];
+                let len = u.int_in_range(1..=buf.len())?;
+                match decoder.read(&mut buf[..len]) {
+                    Ok(0) => break Ok(()),
+                    Ok(n) => actual.extend_from_slice(&buf[..n]),
+                    Err(e) => {
+                        assert_eq!(std::io::ErrorKind::InvalidData, e.kind());
+                        let e = e.get_ref().and_then(|e| e.downcast_ref::<DecodeError>());
+                        break Err(*e.unwrap());
+                    }
+                }
+            };
+            assert_eq!(expected_result, actual_result);
+            assert_eq!(expected, actual);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_encoder_decoder() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut encoder = Encoder::new(Vec::new());
+            encoder.write_all(&input).unwrap();
+            let encoded = encoder.finish().unwrap();
+            let mut actual = Vec::new();
+            Decoder::new(&encoded[..]).read_to_end(&mut actual).unwrap();
+            assert_eq!(input, actual);
+            Ok(())
+        });
+    }
+
+    fn random_split<'a>(
+        u: &mut Unstructured<'_>,
+        mut input: &'a [u8],
+    ) -> arbitrary::Result<Vec<&'a [u8]>> {
+        let mut pieces = Vec::new();
+        while !input.is_empty() {
+            let n = u.int_in_range(1..=input.len())?;
+            let (head, tail) = input.split_at(n);
+            pieces.push(head);
+            input = tail;
+        }
+        Ok(pieces)
+    }
+}
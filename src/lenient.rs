@@ -0,0 +1,284 @@
+use crate::decode;
+use crate::DecodeError;
+use crate::Input;
+use crate::IntoInput;
+use crate::Output;
+use crate::SkipInput;
+
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+/// Decode human-typed `input` byte sequence using BASE32 encoding and write the resulting byte
+/// sequence to `output`.
+///
+/// This is a shorthand for [`decode`] with [`LenientInput`]. Returns the substitutions that were
+/// made.
+///
+/// Use this function for the strings that were typed by the user, and [`decode`] for the file
+/// names.
+///
+/// ```rust
+/// use base32_fs::decode_lenient;
+///
+/// let mut output: Vec<u8> = Vec::new();
+/// let substitutions = decode_lenient(b"D1JP-RV3F", &mut output).unwrap();
+/// assert_eq!(b"hello", output.as_slice());
+/// assert!(substitutions.uppercase());
+/// assert!(substitutions.hyphens());
+/// ```
+pub fn decode_lenient<I: IntoInput<8>, O: Output + ?Sized>(
+    input: I,
+    output: &mut O,
+) -> Result<Substitutions, DecodeError> {
+    let mut input = LenientInput::new(input);
+    decode(&mut input, output)?;
+    Ok(input.substitutions())
+}
+
+/// An [`Input`] adapter for human-typed strings.
+///
+/// Unlike the canonical input this adapter accepts uppercase letters, "o" and "O" instead of "0",
+/// "i", "I", "l" and "L" instead of "1", and ignores hyphens. The errors report the positions and
+/// the characters of the original input.
+///
+/// ```rust
+/// use base32_fs::{decode, LenientInput};
+///
+/// let mut input = LenientInput::new(b"D1JP-RV3F");
+/// let mut output: Vec<u8> = Vec::new();
+/// decode(&mut input, &mut output).unwrap();
+/// assert_eq!(b"hello", output.as_slice());
+/// assert!(input.substitutions().uppercase());
+/// ```
+#[derive(Clone)]
+pub struct LenientInput<I> {
+    inner: SkipInput<I>,
+    substitutions: Substitutions,
+}
+
+impl<I: Input<8>> LenientInput<I> {
+    /// Create new input that reads the human-typed string from `input`.
+    pub fn new<T: IntoInput<8, Input = I>>(input: T) -> Self {
+        Self {
+            inner: SkipInput::new(input.into_input()),
+            substitutions: Substitutions(0),
+        }
+    }
+
+    /// Returns the substitutions that were made in the characters consumed so far.
+    pub const fn substitutions(&self) -> Substitutions {
+        self.substitutions
+    }
+}
+
+impl<I: Input<8>> Input<8> for LenientInput<I> {
+    fn next_chunk(&mut self) -> Option<&[u8]> {
+        let substitutions = &mut self.substitutions;
+        self.inner.next_chunk(|ch| substitutions.normalize_char(ch))
+    }
+
+    fn remainder(&self) -> &[u8] {
+        self.inner.remainder()
+    }
+
+    fn map_error(&self, error: DecodeError) -> DecodeError {
+        self.inner.map_error(error)
+    }
+}
+
+/// Convert human-typed `input` to canonical BASE32-encoded string.
+///
+/// Uppercase letters are converted to lowercase, "o" and "O" are replaced with "0", "i", "I", "l"
+/// and "L" are replaced with "1", and hyphens are removed. Other characters are left as is, i.e.
+/// the result is not validated. Returns the string and the substitutions that were made; the
+/// string is borrowed if no substitutions were made.
+///
+/// ```rust
+/// use base32_fs::normalize;
+///
+/// let (string, substitutions) = normalize("D1JP-RV3F");
+/// assert_eq!("d1jprv3f", string);
+/// assert!(substitutions.uppercase());
+/// assert!(!substitutions.zero());
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn normalize(input: &str) -> (Cow<'_, str>, Substitutions) {
+    let mut substitutions = Substitutions::default();
+    for ch in input.bytes() {
+        substitutions.normalize_char(ch);
+    }
+    if substitutions.is_empty() {
+        return (Cow::Borrowed(input), substitutions);
+    }
+    let mut output = String::with_capacity(input.len());
+    for ch in input.chars() {
+        if !ch.is_ascii() {
+            output.push(ch);
+            continue;
+        }
+        if let Some(ch) = Substitutions::default().normalize_char(ch as u8) {
+            output.push(ch.into());
+        }
+    }
+    (Cow::Owned(output), substitutions)
+}
+
+/// The substitutions that were made by [`LenientInput`] or [`normalize`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Substitutions(u8);
+
+impl Substitutions {
+    const UPPERCASE: u8 = 1 << 0;
+    const ZERO: u8 = 1 << 1;
+    const ONE: u8 = 1 << 2;
+    const HYPHENS: u8 = 1 << 3;
+
+    /// Returns `true` if no substitutions were made, i.e. the input was canonical.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if uppercase letters were converted to lowercase.
+    ///
+    /// "O", "I" and "L" are reported by [`zero`](Self::zero) and [`one`](Self::one) instead.
+    #[inline]
+    pub const fn uppercase(&self) -> bool {
+        self.0 & Self::UPPERCASE != 0
+    }
+
+    /// Returns `true` if "o" or "O" were replaced with "0".
+    #[inline]
+    pub const fn zero(&self) -> bool {
+        self.0 & Self::ZERO != 0
+    }
+
+    /// Returns `true` if "i", "I", "l" or "L" were replaced with "1".
+    #[inline]
+    pub const fn one(&self) -> bool {
+        self.0 & Self::ONE != 0
+    }
+
+    /// Returns `true` if hyphens were removed.
+    #[inline]
+    pub const fn hyphens(&self) -> bool {
+        self.0 & Self::HYPHENS != 0
+    }
+
+    /// Returns the canonical character or `None` if the character has to be removed.
+    #[inline]
+    fn normalize_char(&mut self, ch: u8) -> Option<u8> {
+        let (ch, substitution) = match ch {
+            b'-' => {
+                self.0 |= Self::HYPHENS;
+                return None;
+            }
+            b'o' | b'O' => (b'0', Self::ZERO),
+            b'i' | b'I' | b'l' | b'L' => (b'1', Self::ONE),
+            // "U" is not in the alphabet in either case.
+            b'A'..=b'Z' if ch != b'U' => (ch.to_ascii_lowercase(), Self::UPPERCASE),
+            _ => return Some(ch),
+        };
+        self.0 |= substitution;
+        Some(ch)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use arbtest::arbtest;
+
+    use crate::decode_to_vec;
+    use crate::encode_to_string;
+    use crate::validate;
+    use crate::DecodeErrorKind;
+
+    #[test]
+    fn test_decode_lenient() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let canonical = encode_to_string(&input);
+            let mut typed = Vec::new();
+            for ch in canonical.bytes() {
+                if u.ratio(1, 10)? {
+                    typed.push(b'-');
+                }
+                let ch = match ch {
+                    b'0' if u.arbitrary()? => *u.choose(b"oO")?,
+                    b'1' if u.arbitrary()? => *u.choose(b"iIlL")?,
+                    ch if u.arbitrary()? => ch.to_ascii_uppercase(),
+                    ch => ch,
+                };
+                typed.push(ch);
+            }
+            let mut output: Vec<u8> = Vec::new();
+            let substitutions = decode_lenient(&typed, &mut output).unwrap();
+            assert_eq!(input, output);
+            assert_eq!(input, decode_to_vec(LenientInput::new(&typed)).unwrap());
+            assert_eq!(Ok(input.len()), validate(LenientInput::new(&typed)));
+            let (a, b) = typed.split_at(u.choose_index(typed.len() + 1)?);
+            let mut split_output: Vec<u8> = Vec::new();
+            assert_eq!(Ok(substitutions), decode_lenient((a, b), &mut split_output));
+            assert_eq!(input, split_output);
+            let typed = core::str::from_utf8(&typed).unwrap();
+            let (normalized, expected) = normalize(typed);
+            assert_eq!(canonical, normalized);
+            assert_eq!(expected, substitutions);
+            assert_eq!(substitutions.is_empty(), typed == canonical);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_normalize() {
+        arbtest(|u| {
+            let input: &str = u.arbitrary()?;
+            let (normalized, substitutions) = normalize(input);
+            assert_eq!(
+                substitutions.is_empty(),
+                matches!(normalized, Cow::Borrowed(..))
+            );
+            let mut expected: Vec<u8> = Vec::new();
+            let expected_result = decode(normalized.as_bytes(), &mut expected);
+            let mut actual: Vec<u8> = Vec::new();
+            let actual_result = decode_lenient(input.as_bytes(), &mut actual);
+            assert_eq!(expected_result.is_ok(), actual_result.is_ok());
+            if let Ok(actual_substitutions) = actual_result {
+                assert_eq!(expected, actual);
+                assert_eq!(substitutions, actual_substitutions);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut output: Vec<u8> = Vec::new();
+        let e = decode_lenient(b"D1JP-RV3U", &mut output).unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidChar, e.kind());
+        assert_eq!(8, e.index());
+        assert_eq!(Some(u16::from(b'U')), e.code_unit());
+        let e = decode_lenient((&b"D1J"[..], &b"P-RV3U"[..]), &mut output).unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidChar, e.kind());
+        assert_eq!(8, e.index());
+        assert_eq!(Some(u16::from(b'U')), e.code_unit());
+        let e = decode_lenient("0-L", &mut output).unwrap_err();
+        assert_eq!(
+            "Non-canonical BASE32 character 'L' at index 2: unused bits are not zero",
+            e.to_string()
+        );
+        let e = decode_lenient(b"D1J-", &mut output).unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidLength, e.kind());
+        assert_eq!(3, e.index());
+        let e = validate(LenientInput::new(b"d1jp-rv3f-0OiU")).unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidChar, e.kind());
+        assert_eq!(13, e.index());
+        assert_eq!(Some(u16::from(b'U')), e.code_unit());
+    }
+}
@@ -9,21 +9,50 @@ extern crate alloc;
 extern crate std;
 
 mod alphabet;
+mod array;
+mod base32_str;
 mod decode;
+mod display;
 mod encode;
 mod error;
+mod group;
 mod input;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod io;
+mod lenient;
+mod macros;
+mod name;
 mod output;
 #[cfg(all(feature = "std", any(unix, windows)))]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 mod path_buf;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd;
+mod swar;
+mod uninit;
+mod wrap;
 
-pub(crate) use self::alphabet::*;
+pub use self::alphabet::*;
+pub use self::array::*;
+pub use self::base32_str::*;
 pub use self::decode::*;
+pub use self::display::*;
 pub use self::encode::*;
 pub use self::error::*;
+pub use self::group::*;
 pub use self::input::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use self::io::*;
+pub use self::lenient::*;
+pub use self::name::*;
 pub use self::output::*;
 #[cfg(all(feature = "std", any(unix, windows)))]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub use self::path_buf::*;
+pub use self::uninit::*;
+pub use self::wrap::*;
@@ -0,0 +1,58 @@
+/// Decode BASE32-encoded string literal at compile time.
+///
+/// Expands to a byte array constant. Invalid and non-canonical strings fail the compilation.
+///
+/// ```rust
+/// use base32_fs::base32;
+///
+/// const HELLO: [u8; 5] = base32!("d1jprv3f");
+/// assert_eq!(b"hello", &HELLO);
+/// ```
+///
+/// ```rust,compile_fail
+/// // Invalid character.
+/// const HELLO: [u8; 5] = base32_fs::base32!("d1jprv3i");
+/// ```
+///
+/// ```rust,compile_fail
+/// // Non-canonical string.
+/// const HELLO: [u8; 1] = base32_fs::base32!("01");
+/// ```
+#[macro_export]
+macro_rules! base32 {
+    ($input: expr) => {{
+        const INPUT: &str = $input;
+        const LEN: usize = match $crate::decoded_len(INPUT.len()) {
+            Some(len) => len,
+            None => panic!("Invalid BASE32 string length"),
+        };
+        const OUTPUT: [u8; LEN] = match $crate::decode_const::<LEN>(INPUT.as_bytes()) {
+            Ok(output) => output,
+            Err(_) => panic!("Invalid BASE32 string"),
+        };
+        OUTPUT
+    }};
+}
+
+/// Encode byte array constant at compile time.
+///
+/// Expands to `&'static str`.
+///
+/// ```rust
+/// use base32_fs::base32_encode;
+///
+/// const HELLO: &str = base32_encode!(*b"hello");
+/// assert_eq!("d1jprv3f", HELLO);
+/// ```
+#[macro_export]
+macro_rules! base32_encode {
+    ($input: expr) => {{
+        const INPUT: &[u8] = &$input;
+        const OUTPUT: [u8; $crate::encoded_len(INPUT.len())] = $crate::encode_const(INPUT);
+        const STRING: &str = match ::core::str::from_utf8(&OUTPUT) {
+            Ok(string) => string,
+            Err(_) => panic!("BASE32 string is always a valid UTF-8 byte sequence"),
+        };
+        STRING
+    }};
+}
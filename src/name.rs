@@ -0,0 +1,158 @@
+use crate::decode_array;
+use crate::encode_array;
+use crate::DecodeError;
+
+/// Fixed-size byte array that is displayed as BASE32-encoded string.
+///
+/// Stores the original bytes (e.g. a hash) and encodes them on demand. Sorting order is consistent
+/// with the sorting order of the encoded strings.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Base32Name<const N: usize>([u8; N]);
+
+impl<const N: usize> Base32Name<N> {
+    /// Create new name from the supplied bytes.
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+
+    /// Get the original bytes.
+    pub const fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+
+    /// Transform into the original bytes.
+    pub const fn into_bytes(self) -> [u8; N] {
+        self.0
+    }
+
+    /// Transform into [`PathBuf`](std::path::PathBuf).
+    #[cfg(all(feature = "std", any(unix, windows)))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn to_path_buf(&self) -> std::path::PathBuf {
+        let mut output = crate::PathBufOutput::with_capacity(crate::encoded_len(N));
+        crate::encode(&self.0[..], &mut output);
+        output.into_path_buf()
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Base32Name<N> {
+    fn from(other: [u8; N]) -> Self {
+        Self(other)
+    }
+}
+
+impl<const N: usize> From<Base32Name<N>> for [u8; N] {
+    fn from(other: Base32Name<N>) -> Self {
+        other.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for Base32Name<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl<const N: usize> core::fmt::Display for Base32Name<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Display::fmt(&encode_array(&self.0), f)
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for Base32Name<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_tuple("Base32Name")
+            .field(&encode_array(&self.0))
+            .finish()
+    }
+}
+
+impl<const N: usize> core::str::FromStr for Base32Name<N> {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        decode_array(s.as_bytes()).map(Self)
+    }
+}
+
+#[cfg(all(feature = "std", any(unix, windows)))]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<const N: usize> TryFrom<&std::path::Path> for Base32Name<N> {
+    type Error = DecodeError;
+
+    fn try_from(other: &std::path::Path) -> Result<Self, Self::Error> {
+        decode_array(crate::PathBufInput::new(other)).map(Self)
+    }
+}
+
+#[cfg(all(feature = "std", any(unix, windows)))]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<const N: usize> TryFrom<&std::ffi::OsStr> for Base32Name<N> {
+    type Error = DecodeError;
+
+    fn try_from(other: &std::ffi::OsStr) -> Result<Self, Self::Error> {
+        std::path::Path::new(other).try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::string::ToString;
+    use arbtest::arbtest;
+
+    use crate::DecodeErrorKind;
+
+    #[test]
+    fn test_display_from_str() {
+        arbtest(|u| {
+            let name: Base32Name<32> = Base32Name::new(u.arbitrary()?);
+            let string = name.to_string();
+            assert_eq!(encode_array(name.as_bytes()).as_str(), string);
+            assert_eq!(name, string.parse().unwrap());
+            assert_eq!(format!("Base32Name({string:?})"), format!("{name:?}"));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_ord() {
+        arbtest(|u| {
+            let a: Base32Name<20> = Base32Name::new(u.arbitrary()?);
+            let b: Base32Name<20> = Base32Name::new(u.arbitrary()?);
+            assert_eq!(a.cmp(&b), a.to_string().cmp(&b.to_string()));
+            Ok(())
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_path() {
+        arbtest(|u| {
+            let name: Base32Name<16> = Base32Name::new(u.arbitrary()?);
+            let path = name.to_path_buf();
+            assert_eq!(std::path::Path::new(&name.to_string()), path);
+            assert_eq!(name, Base32Name::try_from(path.as_path()).unwrap());
+            assert_eq!(name, Base32Name::try_from(path.as_os_str()).unwrap());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_errors() {
+        let e = "d1jprv3f".parse::<Base32Name<4>>().unwrap_err();
+        assert_eq!(
+            "BASE32 string of length 8 decodes to 5 bytes, expected 4",
+            e.to_string()
+        );
+        let e = "d1jprv3i".parse::<Base32Name<5>>().unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidChar, e.kind());
+        let string: String = "d1jprv3f".into();
+        assert_eq!(
+            b"hello",
+            string.parse::<Base32Name<5>>().unwrap().as_bytes()
+        );
+    }
+}
@@ -1,9 +1,24 @@
-/// An output of [`encode`](crate::encode) or [`decode`](crate::decode).
+use crate::OutputFull;
+
+/// An output of [`decode`](crate::decode) or [`encode`](crate::encode).
 ///
-/// This is a simpler alternative to [`Write`](std::io::Write).
+/// This is a simpler alternative to [`Write`](std::io::Write). See also [`EncodeOutput`] for the
+/// outputs that can only be used with [`encode`](crate::encode).
 pub trait Output {
     /// Output one byte.
     fn push(&mut self, ch: u8);
+
+    /// Output a group of bytes.
+    ///
+    /// [`encode`](crate::encode) and [`decode`](crate::decode) call this method for each group of
+    /// 8 and 5 bytes respectively. The default implementation calls [`push`](Self::push) for each
+    /// byte.
+    #[inline]
+    fn push_slice(&mut self, bytes: &[u8]) {
+        for ch in bytes {
+            self.push(*ch);
+        }
+    }
 }
 
 impl<T: From<u8>> Output for &mut [T] {
@@ -13,6 +28,15 @@ impl<T: From<u8>> Output for &mut [T] {
         a[0] = ch.into();
         *self = b;
     }
+
+    #[inline]
+    fn push_slice(&mut self, bytes: &[u8]) {
+        let (a, b) = core::mem::take(self).split_at_mut(bytes.len());
+        for (dst, src) in a.iter_mut().zip(bytes) {
+            *dst = (*src).into();
+        }
+        *self = b;
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -21,4 +45,222 @@ impl<T: From<u8>> Output for alloc::vec::Vec<T> {
     fn push(&mut self, ch: u8) {
         alloc::vec::Vec::<T>::push(self, ch.into());
     }
+
+    #[inline]
+    fn push_slice(&mut self, bytes: &[u8]) {
+        self.extend(bytes.iter().map(|ch| T::from(*ch)));
+    }
+}
+
+/// An output of [`try_encode`](crate::try_encode) or [`try_decode`](crate::try_decode).
+///
+/// Unlike [`Output`] this trait allows to check in advance that the output has enough space.
+pub trait TryOutput: Output {
+    /// Make sure that `additional` bytes can be pushed to the output.
+    ///
+    /// Returns [`OutputFull`] error with the number of bytes that fit if the output is too small.
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull>;
+}
+
+impl<T: From<u8>> TryOutput for &mut [T] {
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull> {
+        if self.len() < additional {
+            return Err(OutputFull::new(self.len(), additional));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<T: From<u8>> TryOutput for alloc::vec::Vec<T> {
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull> {
+        alloc::vec::Vec::<T>::try_reserve(self, additional)
+            .map_err(|_| OutputFull::new(self.capacity() - self.len(), additional))
+    }
+}
+
+/// An output of [`encode`](crate::encode).
+///
+/// Unlike [`Output`] this trait is implemented for strings: BASE32-encoded strings consist of ASCII
+/// characters only, whereas decoded byte sequences are arbitrary. Any [`Output`] is also an
+/// [`EncodeOutput`].
+///
+/// ```rust
+/// use base32_fs::encode;
+///
+/// let mut output = String::new();
+/// encode(b"hello", &mut output);
+/// assert_eq!("d1jprv3f", output);
+/// ```
+///
+/// Decoding to a string doesn't compile.
+///
+/// ```rust,compile_fail,E0277
+/// use base32_fs::decode;
+///
+/// let mut output = String::new();
+/// decode("zw", &mut output);
+/// ```
+pub trait EncodeOutput {
+    /// Output a group of BASE32 characters.
+    ///
+    /// [`encode`](crate::encode) calls this method for each group of 8 characters. The characters
+    /// are always ASCII; string outputs panic otherwise.
+    fn push_chars(&mut self, chars: &[u8]);
+}
+
+impl<T: Output + ?Sized> EncodeOutput for T {
+    #[inline]
+    fn push_chars(&mut self, chars: &[u8]) {
+        self.push_slice(chars);
+    }
+}
+
+/// An output of [`try_encode`](crate::try_encode).
+///
+/// Unlike [`EncodeOutput`] this trait allows to check in advance that the output has enough space.
+/// Any [`TryOutput`] is also a [`TryEncodeOutput`].
+pub trait TryEncodeOutput: EncodeOutput {
+    /// Make sure that `additional` characters can be pushed to the output.
+    ///
+    /// Returns [`OutputFull`] error with the number of characters that fit if the output is too
+    /// small.
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull>;
+}
+
+impl<T: TryOutput + ?Sized> TryEncodeOutput for T {
+    #[inline]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull> {
+        TryOutput::try_reserve(self, additional)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl EncodeOutput for alloc::string::String {
+    #[inline]
+    fn push_chars(&mut self, chars: &[u8]) {
+        self.push_str(ascii_str(chars));
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl TryEncodeOutput for alloc::string::String {
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull> {
+        alloc::string::String::try_reserve(self, additional)
+            .map_err(|_| OutputFull::new(self.capacity() - self.len(), additional))
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl EncodeOutput for std::ffi::OsString {
+    #[inline]
+    fn push_chars(&mut self, chars: &[u8]) {
+        self.push(ascii_str(chars));
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl TryEncodeOutput for std::ffi::OsString {
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull> {
+        std::ffi::OsString::try_reserve(self, additional)
+            .map_err(|_| OutputFull::new(self.capacity() - self.len(), additional))
+    }
+}
+
+/// An adapter that implements [`EncodeOutput`] for any [`fmt::Write`](core::fmt::Write).
+///
+/// BASE32-encoded strings are written as is. The first error is stored and returned by
+/// [`finish`](Self::finish); the subsequent characters are ignored.
+pub struct FmtOutput<W> {
+    writer: W,
+    result: core::fmt::Result,
+}
+
+impl<W: core::fmt::Write> FmtOutput<W> {
+    /// Create new output from the supplied writer.
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer,
+            result: Ok(()),
+        }
+    }
+
+    /// Returns the writer or the first error that occurred.
+    pub fn finish(self) -> Result<W, core::fmt::Error> {
+        self.result?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: core::fmt::Write> EncodeOutput for FmtOutput<W> {
+    #[inline]
+    fn push_chars(&mut self, chars: &[u8]) {
+        let s = ascii_str(chars);
+        if self.result.is_ok() {
+            self.result = self.writer.write_str(s);
+        }
+    }
+}
+
+/// Converts BASE32 characters to [`str`].
+///
+/// Panics if the characters are not ASCII.
+#[inline]
+fn ascii_str(chars: &[u8]) -> &str {
+    assert!(chars.is_ascii(), "BASE32 characters are not ASCII");
+    // SAFETY: ASCII byte sequence is a valid UTF-8 byte sequence.
+    unsafe { core::str::from_utf8_unchecked(chars) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use arbtest::arbtest;
+    use std::ffi::OsString;
+
+    use crate::encode;
+
+    #[test]
+    fn test_strings() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input[..], &mut expected);
+            let expected = String::from_utf8(expected).unwrap();
+            let mut string = String::new();
+            encode(&input[..], &mut string);
+            assert_eq!(expected, string);
+            let mut os_string = OsString::new();
+            encode(&input[..], &mut os_string);
+            assert_eq!(OsString::from(&expected), os_string);
+            let mut output = FmtOutput::new(String::new());
+            encode(&input[..], &mut output);
+            assert_eq!(expected, output.finish().unwrap());
+            assert_eq!(expected, crate::encode_to_string(&input));
+            assert_eq!(input, crate::decode_to_vec(expected.as_bytes()).unwrap());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_fmt_error() {
+        struct Full;
+
+        impl core::fmt::Write for Full {
+            fn write_str(&mut self, _s: &str) -> core::fmt::Result {
+                Err(core::fmt::Error)
+            }
+        }
+
+        let mut output = FmtOutput::new(Full);
+        encode(b"hello", &mut output);
+        assert!(output.finish().is_err());
+    }
 }
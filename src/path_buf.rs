@@ -1,5 +1,8 @@
+use crate::DecodeError;
 use crate::Input;
 use crate::Output;
+use crate::OutputFull;
+use crate::TryOutput;
 
 use alloc::vec::Vec;
 use std::ffi::OsString;
@@ -61,6 +64,12 @@ impl PathBufOutput {
     }
 }
 
+impl Default for PathBufOutput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl From<PathBufOutput> for PathBuf {
     fn from(other: PathBufOutput) -> Self {
         other.into_path_buf()
@@ -79,11 +88,28 @@ impl Output for PathBufOutput {
         let ch = ch.into();
         self.bytes.push(ch)
     }
+
+    #[inline]
+    fn push_slice(&mut self, bytes: &[u8]) {
+        #[cfg(unix)]
+        self.bytes.extend_from_slice(bytes);
+        #[cfg(windows)]
+        self.bytes.extend(bytes.iter().map(|ch| u16::from(*ch)));
+    }
+}
+
+impl TryOutput for PathBufOutput {
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull> {
+        self.bytes
+            .try_reserve(additional)
+            .map_err(|_| OutputFull::new(self.bytes.capacity() - self.bytes.len(), additional))
+    }
 }
 
 /// An implementation of [`Input`](crate::Input) for file system paths.
 ///
 /// Works on both Unix-like and Windows platforms.
+#[derive(Clone)]
 pub struct PathBufInput<'a> {
     #[cfg(unix)]
     inner: &'a [u8],
@@ -113,9 +139,17 @@ impl Input<8> for PathBufInput<'_> {
         Input::<8>::next_chunk(&mut self.inner)
     }
 
+    fn next_chunks(&mut self, count: usize) -> Option<&[u8]> {
+        Input::<8>::next_chunks(&mut self.inner, count)
+    }
+
     fn remainder(&self) -> &[u8] {
         Input::<8>::remainder(&self.inner)
     }
+
+    fn map_error(&self, error: DecodeError) -> DecodeError {
+        Input::<8>::map_error(&self.inner, error)
+    }
 }
 
 #[cfg(unix)]
@@ -129,16 +163,22 @@ use self::unix::*;
 
 #[cfg(windows)]
 mod windows {
+    use crate::DecodeError;
     use crate::Input;
 
     pub use std::os::windows::ffi::EncodeWide;
     pub use std::os::windows::ffi::OsStrExt;
     pub use std::os::windows::ffi::OsStringExt;
 
+    #[derive(Clone)]
     pub struct WideCharIter<'a> {
         iter: EncodeWide<'a>,
         chunk: [u8; 8],
-        remainder_len: usize,
+        // Original code units of the current chunk.
+        code_units: [u16; 8],
+        // The position of the current chunk in the input.
+        offset: usize,
+        len: usize,
     }
 
     impl<'a> WideCharIter<'a> {
@@ -146,28 +186,40 @@ mod windows {
             Self {
                 iter,
                 chunk: [0; 8],
-                remainder_len: 0,
+                code_units: [0; 8],
+                offset: 0,
+                len: 0,
             }
         }
     }
 
     impl<'a> Input<8> for WideCharIter<'a> {
         fn next_chunk(&mut self) -> Option<&[u8]> {
+            self.offset += self.len;
+            self.len = 0;
             for i in 0..8 {
                 match self.iter.next() {
-                    // Map to an invalid character to trigger an error.
-                    Some(x) => self.chunk[i] = x.try_into().unwrap_or(u8::MAX),
-                    None => {
-                        self.remainder_len = i;
-                        return None;
+                    Some(x) => {
+                        // Map to an invalid character to trigger an error.
+                        self.chunk[i] = x.try_into().unwrap_or(u8::MAX);
+                        self.code_units[i] = x;
+                        self.len += 1;
                     }
+                    None => return None,
                 }
             }
             Some(&self.chunk[..])
         }
 
         fn remainder(&self) -> &[u8] {
-            &self.chunk[..self.remainder_len]
+            &self.chunk[..self.len]
+        }
+
+        fn map_error(&self, error: DecodeError) -> DecodeError {
+            match error.index().checked_sub(self.offset) {
+                Some(i) if i < self.len => error.with_code_unit(self.code_units[i]),
+                _ => error,
+            }
         }
     }
 }
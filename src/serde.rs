@@ -0,0 +1,330 @@
+//! (De)serialize byte sequences as BASE32-encoded strings.
+//!
+//! Use this module via `#[serde(with = "base32_fs::serde")]`. Human-readable formats (JSON, TOML
+//! etc.) use BASE32-encoded strings, binary formats use raw bytes.
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Manifest {
+//!     #[serde(with = "base32_fs::serde")]
+//!     hash: [u8; 5],
+//! }
+//!
+//! let manifest: Manifest = serde_json::from_str(r#"{"hash":"d1jprv3f"}"#).unwrap();
+//! assert_eq!(b"hello", &manifest.hash);
+//! ```
+
+use core::fmt::Formatter;
+
+use ::serde::de::Error;
+use ::serde::de::SeqAccess;
+use ::serde::de::Visitor;
+use ::serde::Deserializer;
+use ::serde::Serializer;
+
+use crate::decode_array;
+use crate::display;
+use crate::Base32Name;
+
+#[cfg(feature = "alloc")]
+use crate::Base32Str;
+#[cfg(feature = "alloc")]
+use crate::Base32String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Serialize `value` as BASE32-encoded string or raw bytes depending on the format.
+pub fn serialize<T: Base32Serde, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value.serialize(serializer)
+}
+
+/// Deserialize `value` from BASE32-encoded string or raw bytes depending on the format.
+pub fn deserialize<'de, T: Base32Serde, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    T::deserialize(deserializer)
+}
+
+/// Types that can be (de)serialized via this module.
+pub trait Base32Serde: Sized {
+    /// Serialize as BASE32-encoded string or raw bytes depending on the format.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Deserialize from BASE32-encoded string or raw bytes depending on the format.
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl<const N: usize> Base32Serde for [u8; N] {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&self[..], serializer)
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(ArrayVisitor)
+        } else {
+            deserializer.deserialize_bytes(ArrayVisitor)
+        }
+    }
+}
+
+impl<const N: usize> Base32Serde for Base32Name<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&self.as_bytes()[..], serializer)
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <[u8; N] as Base32Serde>::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Base32Serde for Vec<u8> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&self[..], serializer)
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(VecVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(VecVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Base32Serde for Base32String {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ::serde::Serialize::serialize(self.as_base32_str(), serializer)
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(StringVisitor)
+        } else {
+            deserializer
+                .deserialize_byte_buf(VecVisitor)
+                .map(|bytes| Base32String::encode(&bytes))
+        }
+    }
+}
+
+impl<const N: usize> ::serde::Serialize for Base32Name<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Base32Serde::serialize(self, serializer)
+    }
+}
+
+impl<'de, const N: usize> ::serde::Deserialize<'de> for Base32Name<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Self as Base32Serde>::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ::serde::Serialize for Base32Str {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(self.as_str())
+        } else {
+            serializer.serialize_bytes(&self.decode_to_vec())
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ::serde::Serialize for Base32String {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Base32Serde::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de> ::serde::Deserialize<'de> for Base32String {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Self as Base32Serde>::deserialize(deserializer)
+    }
+}
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(&display(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+struct ArrayVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for ArrayVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut Formatter) -> core::fmt::Result {
+        write!(f, "BASE32-encoded string or {} bytes", N)
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        decode_array(value.as_bytes()).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        value
+            .try_into()
+            .map_err(|_| E::invalid_length(value.len(), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut array = [0_u8; N];
+        for (i, byte) in array.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(A::Error::invalid_length(N + 1, &self));
+        }
+        Ok(array)
+    }
+}
+
+#[cfg(feature = "alloc")]
+struct VecVisitor;
+
+#[cfg(feature = "alloc")]
+impl<'de> Visitor<'de> for VecVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut Formatter) -> core::fmt::Result {
+        f.write_str("BASE32-encoded string or bytes")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        Base32Str::new(value)
+            .map(Base32Str::decode_to_vec)
+            .map_err(E::custom)
+    }
+
+    fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(value)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "alloc")]
+struct StringVisitor;
+
+#[cfg(feature = "alloc")]
+impl<'de> Visitor<'de> for StringVisitor {
+    type Value = Base32String;
+
+    fn expecting(&self, f: &mut Formatter) -> core::fmt::Result {
+        f.write_str("BASE32-encoded string")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_string<E: Error>(self, value: alloc::string::String) -> Result<Self::Value, E> {
+        Base32String::new(value).map_err(E::custom)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use ::serde::Deserialize;
+    use ::serde::Serialize;
+    use alloc::string::String;
+    use alloc::string::ToString;
+    use arbtest::arbtest;
+    use serde_test::assert_de_tokens_error;
+    use serde_test::assert_tokens;
+    use serde_test::Configure;
+    use serde_test::Token;
+
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+    struct Manifest {
+        #[serde(with = "crate::serde")]
+        array: [u8; 5],
+        #[serde(with = "crate::serde")]
+        vec: Vec<u8>,
+        #[serde(with = "crate::serde")]
+        name: Base32Name<5>,
+        string: Base32String,
+    }
+
+    #[test]
+    fn test_tokens() {
+        let name = Base32Name::new(*b"hello");
+        assert_tokens(&name.readable(), &[Token::Str("d1jprv3f")]);
+        assert_tokens(&name.compact(), &[Token::Bytes(b"hello")]);
+        let string = Base32String::encode(b"hello");
+        assert_tokens(&string.clone().readable(), &[Token::Str("d1jprv3f")]);
+        assert_tokens(&string.compact(), &[Token::ByteBuf(b"hello")]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_de_tokens_error::<serde_test::Readable<Base32Name<5>>>(
+            &[Token::Str("d1jprv3i")],
+            "Invalid BASE32 character 'i' at index 7",
+        );
+        assert_de_tokens_error::<serde_test::Readable<Base32Name<5>>>(
+            &[Token::Str("01")],
+            "Non-canonical BASE32 character '1' at index 1: unused bits are not zero",
+        );
+        assert_de_tokens_error::<serde_test::Compact<Base32Name<5>>>(
+            &[Token::Bytes(b"hell")],
+            "invalid length 4, expected BASE32-encoded string or 5 bytes",
+        );
+        assert_de_tokens_error::<serde_test::Readable<Base32String>>(
+            &[Token::Str("d1j")],
+            "Invalid BASE32 string length 3: 1, 3 or 6 modulo 8",
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        arbtest(|u| {
+            let vec: Vec<u8> = u.arbitrary()?;
+            let expected = Manifest {
+                array: u.arbitrary()?,
+                name: Base32Name::new(u.arbitrary()?),
+                string: Base32String::encode(&vec),
+                vec,
+            };
+            let json = serde_json::to_string(&expected).unwrap();
+            let actual: Manifest = serde_json::from_str(&json).unwrap();
+            assert_eq!(expected, actual);
+            let value = serde_json::to_value(&expected).unwrap();
+            assert_eq!(
+                Some(expected.name.to_string().as_str()),
+                value["name"].as_str()
+            );
+            assert_eq!(
+                Some(String::from(expected.string).as_str()),
+                value["vec"].as_str()
+            );
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,496 @@
+//! SIMD encoding and decoding for x86_64 with runtime CPU feature detection.
+//!
+//! The kernels use the same arithmetic as the [`swar`](crate::swar) module, but process 2 (SSE4.1)
+//! or 4 (AVX2) groups of 8 characters at once.
+
+use crate::EncodeOutput;
+use crate::Output;
+use crate::CHARS;
+
+/// A SIMD kernel that is supported by the current CPU.
+///
+/// Can only be created via [`detect`](Self::detect).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Kernel(Level);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Level {
+    Avx2,
+    Sse41,
+}
+
+impl Kernel {
+    /// Detect the fastest kernel that is supported by the current CPU.
+    #[inline]
+    pub(crate) fn detect() -> Option<Self> {
+        if std::arch::is_x86_feature_detected!("avx2") {
+            return Some(Self(Level::Avx2));
+        }
+        if std::arch::is_x86_feature_detected!("sse4.1")
+            && std::arch::is_x86_feature_detected!("ssse3")
+        {
+            return Some(Self(Level::Sse41));
+        }
+        None
+    }
+
+    /// The number of characters that are decoded at once by
+    /// [`decode_block`](Self::decode_block).
+    #[inline]
+    pub(crate) const fn decode_block_len(self) -> usize {
+        match self.0 {
+            Level::Avx2 => 32,
+            Level::Sse41 => 16,
+        }
+    }
+
+    /// The number of bytes that are encoded at once by [`encode`](Self::encode).
+    #[inline]
+    pub(crate) const fn encode_block_len(self) -> usize {
+        match self.0 {
+            Level::Avx2 => 20,
+            Level::Sse41 => 10,
+        }
+    }
+
+    /// Encode as many 5-byte groups as possible and return the rest of the input.
+    #[inline]
+    pub(crate) fn encode<'a, O: EncodeOutput + ?Sized>(
+        self,
+        mut input: &'a [u8],
+        output: &mut O,
+    ) -> &'a [u8] {
+        match self.0 {
+            Level::Avx2 => {
+                while input.len() >= 20 {
+                    let block = load_block::<32>(input);
+                    // SAFETY: AVX2 support was checked in `detect`.
+                    let chars = unsafe { avx2::encode_block(&block) };
+                    output.push_chars(&chars);
+                    input = &input[20..];
+                }
+            }
+            Level::Sse41 => {
+                while input.len() >= 10 {
+                    let block = load_block::<16>(input);
+                    // SAFETY: SSE4.1 and SSSE3 support was checked in `detect`.
+                    let chars = unsafe { sse41::encode_block(&block) };
+                    output.push_chars(&chars);
+                    input = &input[10..];
+                }
+            }
+        }
+        input
+    }
+
+    /// Decode [`decode_block_len`](Self::decode_block_len) characters.
+    ///
+    /// Returns `false` and doesn't write anything to the output if the block is invalid.
+    #[inline]
+    pub(crate) fn decode_block<O: Output + ?Sized>(self, block: &[u8], output: &mut O) -> bool {
+        match self.0 {
+            Level::Avx2 => {
+                let Ok(block) = block.try_into() else {
+                    return false;
+                };
+                // SAFETY: AVX2 support was checked in `detect`.
+                let Some(bytes) = (unsafe { avx2::decode_block(block) }) else {
+                    return false;
+                };
+                output.push_slice(&bytes);
+            }
+            Level::Sse41 => {
+                let Ok(block) = block.try_into() else {
+                    return false;
+                };
+                // SAFETY: SSE4.1 and SSSE3 support was checked in `detect`.
+                let Some(bytes) = (unsafe { sse41::decode_block(block) }) else {
+                    return false;
+                };
+                output.push_slice(&bytes);
+            }
+        }
+        true
+    }
+
+    /// Returns `true` if all [`decode_block_len`](Self::decode_block_len) characters are valid.
+    #[inline]
+    pub(crate) fn is_valid_block(self, block: &[u8]) -> bool {
+        match self.0 {
+            Level::Avx2 => match block.try_into() {
+                // SAFETY: AVX2 support was checked in `detect`.
+                Ok(block) => unsafe { avx2::is_valid_block(block) },
+                Err(_) => false,
+            },
+            Level::Sse41 => match block.try_into() {
+                // SAFETY: SSE4.1 and SSSE3 support was checked in `detect`.
+                Ok(block) => unsafe { sse41::is_valid_block(block) },
+                Err(_) => false,
+            },
+        }
+    }
+}
+
+/// Copy the first `N` bytes of the input padding with zeroes if necessary.
+#[inline]
+fn load_block<const N: usize>(input: &[u8]) -> [u8; N] {
+    match input.first_chunk::<N>() {
+        Some(block) => *block,
+        None => {
+            let mut block = [0_u8; N];
+            block[..input.len()].copy_from_slice(input);
+            block
+        }
+    }
+}
+
+/// The first half of the alphabet.
+const CHARS_LO: [u8; 16] = chars(0);
+
+/// The second half of the alphabet.
+const CHARS_HI: [u8; 16] = chars(16);
+
+const fn chars(offset: usize) -> [u8; 16] {
+    let mut chars = [0_u8; 16];
+    let mut i = 0;
+    while i < 16 {
+        chars[i] = CHARS[offset + i];
+        i += 1;
+    }
+    chars
+}
+
+/// For each of the 8 characters of the first group select two consecutive bytes that contain
+/// the character's bits (in big-endian order).
+const ENCODE_SHUFFLE_0: [u8; 16] = [1, 0, 1, 0, 2, 1, 2, 1, 3, 2, 4, 3, 4, 3, 0x80, 4];
+
+/// The same as [`ENCODE_SHUFFLE_0`] but for the second group.
+const ENCODE_SHUFFLE_1: [u8; 16] = [6, 5, 6, 5, 7, 6, 7, 6, 8, 7, 9, 8, 9, 8, 0x80, 9];
+
+/// Multipliers that shift each 16-bit lane right by 11, 6, 9, 4, 7, 10, 5 and 8 bits respectively
+/// when used with `mulhi`.
+const ENCODE_SHIFT: [u8; 16] = [
+    0x20, 0x00, 0x00, 0x04, 0x80, 0x00, 0x00, 0x10, 0x00, 0x02, 0x40, 0x00, 0x00, 0x08, 0x00, 0x01,
+];
+
+/// Select 5 bytes of each 40-bit lane in big-endian order.
+const DECODE_SHUFFLE: [u8; 16] = [
+    4, 3, 2, 1, 0, 12, 11, 10, 9, 8, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+];
+
+mod sse41 {
+    use core::arch::x86_64::*;
+
+    use super::CHARS_HI;
+    use super::CHARS_LO;
+    use super::DECODE_SHUFFLE;
+    use super::ENCODE_SHIFT;
+    use super::ENCODE_SHUFFLE_0;
+    use super::ENCODE_SHUFFLE_1;
+
+    /// Encode 2 groups of 5 bytes that are stored at the beginning of the block.
+    #[target_feature(enable = "sse4.1,ssse3")]
+    pub(super) fn encode_block(block: &[u8; 16]) -> [u8; 16] {
+        let x = load(block);
+        let a = _mm_shuffle_epi8(x, load(&ENCODE_SHUFFLE_0));
+        let b = _mm_shuffle_epi8(x, load(&ENCODE_SHUFFLE_1));
+        let shift = load(&ENCODE_SHIFT);
+        let mask = _mm_set1_epi16(0b11111);
+        let a = _mm_and_si128(_mm_mulhi_epu16(a, shift), mask);
+        let b = _mm_and_si128(_mm_mulhi_epu16(b, shift), mask);
+        let indices = _mm_packus_epi16(a, b);
+        store(to_chars(indices))
+    }
+
+    /// Decode 2 groups of 8 characters.
+    #[target_feature(enable = "sse4.1,ssse3")]
+    pub(super) fn decode_block(block: &[u8; 16]) -> Option<[u8; 10]> {
+        let x = load(block);
+        if !is_valid(x) {
+            return None;
+        }
+        let ge = |k: u8| _mm_cmpgt_epi8(x, _mm_set1_epi8((k - 1) as i8));
+        // Map characters to indices (masks are -1, hence addition).
+        let x = _mm_sub_epi8(x, _mm_set1_epi8(b'0' as i8));
+        let x = _mm_sub_epi8(x, _mm_and_si128(ge(b'a'), _mm_set1_epi8(39)));
+        let x = _mm_add_epi8(x, ge(b'j'));
+        let x = _mm_add_epi8(x, ge(b'm'));
+        let x = _mm_add_epi8(x, ge(b'p'));
+        let x = _mm_add_epi8(x, ge(b'v'));
+        // Gather 8 x 5 bits into 4 x 10 bits, 2 x 20 bits and finally 40 bits.
+        let x = _mm_maddubs_epi16(x, _mm_set1_epi16(0x01_20));
+        let x = _mm_madd_epi16(x, _mm_set1_epi32(0x0001_0400));
+        let x = _mm_or_si128(_mm_slli_epi64(x, 20), _mm_srli_epi64(x, 32));
+        let x = _mm_shuffle_epi8(x, load(&DECODE_SHUFFLE));
+        let bytes = store(x);
+        let mut output = [0_u8; 10];
+        output.copy_from_slice(&bytes[..10]);
+        Some(output)
+    }
+
+    /// Returns `true` if all 16 characters are valid.
+    #[target_feature(enable = "sse4.1,ssse3")]
+    pub(super) fn is_valid_block(block: &[u8; 16]) -> bool {
+        is_valid(load(block))
+    }
+
+    #[target_feature(enable = "sse4.1,ssse3")]
+    fn is_valid(x: __m128i) -> bool {
+        // Non-ASCII characters are negative and fail all the comparisons.
+        let ge = |k: u8| _mm_cmpgt_epi8(x, _mm_set1_epi8((k - 1) as i8));
+        let eq = |k: u8| _mm_cmpeq_epi8(x, _mm_set1_epi8(k as i8));
+        let digit = _mm_andnot_si128(ge(b'9' + 1), ge(b'0'));
+        let letter = _mm_andnot_si128(ge(b'z' + 1), ge(b'a'));
+        let excluded = _mm_or_si128(
+            _mm_or_si128(eq(b'i'), eq(b'l')),
+            _mm_or_si128(eq(b'o'), eq(b'u')),
+        );
+        let valid = _mm_or_si128(digit, _mm_andnot_si128(excluded, letter));
+        _mm_movemask_epi8(valid) == 0xffff
+    }
+
+    /// Map 5-bit indices to characters.
+    #[target_feature(enable = "sse4.1,ssse3")]
+    fn to_chars(indices: __m128i) -> __m128i {
+        let lo = _mm_shuffle_epi8(load(&CHARS_LO), indices);
+        let hi = _mm_shuffle_epi8(load(&CHARS_HI), indices);
+        let is_hi = _mm_cmpgt_epi8(indices, _mm_set1_epi8(15));
+        _mm_blendv_epi8(lo, hi, is_hi)
+    }
+
+    #[inline]
+    pub(super) fn load(bytes: &[u8; 16]) -> __m128i {
+        // SAFETY: Reading 16 bytes from 16-byte array. SSE2 is supported by all x86_64 CPUs.
+        unsafe { _mm_loadu_si128(bytes.as_ptr().cast()) }
+    }
+
+    #[inline]
+    fn store(x: __m128i) -> [u8; 16] {
+        let mut bytes = [0_u8; 16];
+        // SAFETY: Writing 16 bytes to 16-byte array. SSE2 is supported by all x86_64 CPUs.
+        unsafe { _mm_storeu_si128(bytes.as_mut_ptr().cast(), x) };
+        bytes
+    }
+}
+
+mod avx2 {
+    use core::arch::x86_64::*;
+
+    use super::sse41::load;
+    use super::CHARS_HI;
+    use super::CHARS_LO;
+    use super::DECODE_SHUFFLE;
+    use super::ENCODE_SHIFT;
+    use super::ENCODE_SHUFFLE_0;
+    use super::ENCODE_SHUFFLE_1;
+
+    /// Encode 4 groups of 5 bytes that are stored at the beginning of the block.
+    #[target_feature(enable = "avx2")]
+    pub(super) fn encode_block(block: &[u8; 32]) -> [u8; 32] {
+        // Groups 0 and 1 go to the lower lane, groups 2 and 3 go to the upper lane.
+        // SAFETY: Reading bytes 0..16 and 10..26 from 32-byte array.
+        let (lo, hi) = unsafe {
+            (
+                _mm_loadu_si128(block.as_ptr().cast()),
+                _mm_loadu_si128(block.as_ptr().add(10).cast()),
+            )
+        };
+        let x = _mm256_inserti128_si256::<1>(_mm256_castsi128_si256(lo), hi);
+        let a = _mm256_shuffle_epi8(x, load2(&ENCODE_SHUFFLE_0));
+        let b = _mm256_shuffle_epi8(x, load2(&ENCODE_SHUFFLE_1));
+        let shift = load2(&ENCODE_SHIFT);
+        let mask = _mm256_set1_epi16(0b11111);
+        let a = _mm256_and_si256(_mm256_mulhi_epu16(a, shift), mask);
+        let b = _mm256_and_si256(_mm256_mulhi_epu16(b, shift), mask);
+        // Packing is done within each lane, hence the order of the groups is preserved.
+        let indices = _mm256_packus_epi16(a, b);
+        store(to_chars(indices))
+    }
+
+    /// Decode 4 groups of 8 characters.
+    #[target_feature(enable = "avx2")]
+    pub(super) fn decode_block(block: &[u8; 32]) -> Option<[u8; 20]> {
+        let x = load256(block);
+        if !is_valid(x) {
+            return None;
+        }
+        let ge = |k: u8| _mm256_cmpgt_epi8(x, _mm256_set1_epi8((k - 1) as i8));
+        // Map characters to indices (masks are -1, hence addition).
+        let x = _mm256_sub_epi8(x, _mm256_set1_epi8(b'0' as i8));
+        let x = _mm256_sub_epi8(x, _mm256_and_si256(ge(b'a'), _mm256_set1_epi8(39)));
+        let x = _mm256_add_epi8(x, ge(b'j'));
+        let x = _mm256_add_epi8(x, ge(b'm'));
+        let x = _mm256_add_epi8(x, ge(b'p'));
+        let x = _mm256_add_epi8(x, ge(b'v'));
+        // Gather 8 x 5 bits into 4 x 10 bits, 2 x 20 bits and finally 40 bits.
+        let x = _mm256_maddubs_epi16(x, _mm256_set1_epi16(0x01_20));
+        let x = _mm256_madd_epi16(x, _mm256_set1_epi32(0x0001_0400));
+        let x = _mm256_or_si256(_mm256_slli_epi64(x, 20), _mm256_srli_epi64(x, 32));
+        let x = _mm256_shuffle_epi8(x, load2(&DECODE_SHUFFLE));
+        let bytes = store(x);
+        let mut output = [0_u8; 20];
+        output[..10].copy_from_slice(&bytes[..10]);
+        output[10..].copy_from_slice(&bytes[16..26]);
+        Some(output)
+    }
+
+    /// Returns `true` if all 32 characters are valid.
+    #[target_feature(enable = "avx2")]
+    pub(super) fn is_valid_block(block: &[u8; 32]) -> bool {
+        is_valid(load256(block))
+    }
+
+    #[target_feature(enable = "avx2")]
+    fn is_valid(x: __m256i) -> bool {
+        // Non-ASCII characters are negative and fail all the comparisons.
+        let ge = |k: u8| _mm256_cmpgt_epi8(x, _mm256_set1_epi8((k - 1) as i8));
+        let eq = |k: u8| _mm256_cmpeq_epi8(x, _mm256_set1_epi8(k as i8));
+        let digit = _mm256_andnot_si256(ge(b'9' + 1), ge(b'0'));
+        let letter = _mm256_andnot_si256(ge(b'z' + 1), ge(b'a'));
+        let excluded = _mm256_or_si256(
+            _mm256_or_si256(eq(b'i'), eq(b'l')),
+            _mm256_or_si256(eq(b'o'), eq(b'u')),
+        );
+        let valid = _mm256_or_si256(digit, _mm256_andnot_si256(excluded, letter));
+        _mm256_movemask_epi8(valid) == -1
+    }
+
+    /// Map 5-bit indices to characters.
+    #[target_feature(enable = "avx2")]
+    fn to_chars(indices: __m256i) -> __m256i {
+        let lo = _mm256_shuffle_epi8(load2(&CHARS_LO), indices);
+        let hi = _mm256_shuffle_epi8(load2(&CHARS_HI), indices);
+        let is_hi = _mm256_cmpgt_epi8(indices, _mm256_set1_epi8(15));
+        _mm256_blendv_epi8(lo, hi, is_hi)
+    }
+
+    /// Load the same 16 bytes into both lanes.
+    #[target_feature(enable = "avx2")]
+    fn load2(bytes: &[u8; 16]) -> __m256i {
+        _mm256_broadcastsi128_si256(load(bytes))
+    }
+
+    #[target_feature(enable = "avx2")]
+    fn load256(bytes: &[u8; 32]) -> __m256i {
+        // SAFETY: Reading 32 bytes from 32-byte array.
+        unsafe { _mm256_loadu_si256(bytes.as_ptr().cast()) }
+    }
+
+    #[target_feature(enable = "avx2")]
+    fn store(x: __m256i) -> [u8; 32] {
+        let mut bytes = [0_u8; 32];
+        // SAFETY: Writing 32 bytes to 32-byte array.
+        unsafe { _mm256_storeu_si256(bytes.as_mut_ptr().cast(), x) };
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use arbtest::arbtest;
+
+    use crate::decode_chunk_scalar;
+    use crate::encode_chunk_scalar;
+    use crate::is_valid_char;
+
+    #[test]
+    fn test_encode() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected = Vec::new();
+            for chunk in input.as_chunks::<5>().0 {
+                expected.extend(encode_chunk_scalar(chunk));
+            }
+            for kernel in kernels() {
+                let mut actual: Vec<u8> = Vec::new();
+                let rest = kernel.encode(&input, &mut actual);
+                assert!(rest.len() < kernel.decode_block_len() / 8 * 5);
+                assert_eq!(input.len() / 5 - rest.len() / 5, actual.len() / 8);
+                assert_eq!(expected[..actual.len()], actual, "kernel = {kernel:?}");
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_decode_block() {
+        arbtest(|u| {
+            let mut block = [0_u8; 32];
+            for ch in block.iter_mut() {
+                *ch = if u.ratio(1, 64)? {
+                    u.arbitrary()?
+                } else {
+                    *u.choose(&CHARS)?
+                };
+            }
+            for kernel in kernels() {
+                let block = &block[..kernel.decode_block_len()];
+                let expected = block.iter().all(|ch| is_valid_char(*ch)).then(|| {
+                    let mut expected = Vec::new();
+                    for chunk in block.as_chunks::<8>().0 {
+                        expected.extend(decode_chunk_scalar(chunk));
+                    }
+                    expected
+                });
+                let mut actual: Vec<u8> = Vec::new();
+                let valid = kernel.decode_block(block, &mut actual);
+                assert_eq!(expected.is_some(), valid, "kernel = {kernel:?}");
+                assert_eq!(expected.unwrap_or_default(), actual, "kernel = {kernel:?}");
+                assert_eq!(valid, kernel.is_valid_block(block), "kernel = {kernel:?}");
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_is_valid_block_exhaustive() {
+        for kernel in kernels() {
+            let len = kernel.decode_block_len();
+            for i in 0..len {
+                for ch in 0..=u8::MAX {
+                    let mut block = [b'0'; 32];
+                    block[i] = ch;
+                    assert_eq!(
+                        is_valid_char(ch),
+                        kernel.is_valid_block(&block[..len]),
+                        "kernel = {kernel:?}, block = {block:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_error() {
+        arbtest(|u| {
+            let len = u.int_in_range(1..=128)? * 8;
+            let mut input = Vec::with_capacity(len);
+            for _ in 0..len {
+                input.push(*u.choose(&CHARS)?);
+            }
+            let i = u.choose_index(len)?;
+            input[i] = b'_';
+            let mut output: Vec<u8> = Vec::new();
+            let error = crate::decode(input.as_slice(), &mut output).unwrap_err();
+            assert_eq!(i, error.index());
+            assert_eq!(Err(error), crate::validate(input.as_slice()));
+            Ok(())
+        });
+    }
+
+    /// Returns all kernels that are supported by the current CPU.
+    fn kernels() -> Vec<Kernel> {
+        let mut kernels = Vec::new();
+        if std::arch::is_x86_feature_detected!("avx2") {
+            kernels.push(Kernel(Level::Avx2));
+        }
+        if std::arch::is_x86_feature_detected!("sse4.1")
+            && std::arch::is_x86_feature_detected!("ssse3")
+        {
+            kernels.push(Kernel(Level::Sse41));
+        }
+        kernels
+    }
+}
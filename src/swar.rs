@@ -0,0 +1,151 @@
+//! Word-at-a-time (SWAR) encoding and decoding.
+//!
+//! Each 8-character group is processed as a single `u64` with one character per byte (the first
+//! character being the most significant byte). The functions are branch-free and don't use lookup
+//! tables.
+
+/// Each byte is `0x01`.
+const ONES: u64 = splat(0x01);
+
+/// Each byte is `0x80`.
+const HIGH_BITS: u64 = splat(0x80);
+
+/// Encode 5 bytes as 8 characters.
+#[cfg(any(test, target_pointer_width = "64"))]
+#[inline]
+pub(crate) const fn encode_chunk(chunk: &[u8; 5]) -> [u8; 8] {
+    let [a, b, c, d, e] = *chunk;
+    let x = u64::from_be_bytes([0, 0, 0, a, b, c, d, e]);
+    // Spread 40 bits over 8 bytes, 5 bits per byte: 2 x 20 bits, 4 x 10 bits, 8 x 5 bits.
+    let x = ((x << 12) & 0x000f_ffff_0000_0000) | (x & 0x0000_0000_000f_ffff);
+    let x = ((x << 6) & 0x03ff_0000_03ff_0000) | (x & 0x0000_03ff_0000_03ff);
+    let x = ((x << 3) & 0x1f00_1f00_1f00_1f00) | (x & 0x001f_001f_001f_001f);
+    // Map indices to characters skipping 'i', 'l', 'o' and 'u'.
+    let x = x + splat(b'0') + 39 * ge(x, 10) + ge(x, 18) + ge(x, 20) + ge(x, 22) + ge(x, 27);
+    x.to_be_bytes()
+}
+
+/// Decode 8 characters as 5 bytes.
+///
+/// Returns `None` if any of the characters is invalid.
+#[cfg(any(test, target_pointer_width = "64"))]
+#[inline]
+pub(crate) const fn decode_chunk(chunk: &[u8; 8]) -> Option<[u8; 5]> {
+    let x = u64::from_be_bytes(*chunk);
+    if !is_valid(x) {
+        return None;
+    }
+    // Map characters to indices.
+    let correction = 39 * ge(x, b'a') + ge(x, b'j') + ge(x, b'm') + ge(x, b'p') + ge(x, b'v');
+    let x = x - splat(b'0') - correction;
+    // Gather 8 x 5 bits into 40 bits: 4 x 10 bits, 2 x 20 bits, 40 bits.
+    let x = ((x & 0x1f00_1f00_1f00_1f00) >> 3) | (x & 0x001f_001f_001f_001f);
+    let x = ((x & 0x03ff_0000_03ff_0000) >> 6) | (x & 0x0000_03ff_0000_03ff);
+    let x = ((x & 0x000f_ffff_0000_0000) >> 12) | (x & 0x0000_0000_000f_ffff);
+    let [_, _, _, a, b, c, d, e] = x.to_be_bytes();
+    Some([a, b, c, d, e])
+}
+
+/// Returns `true` if all 8 characters are valid.
+#[inline]
+pub(crate) const fn is_valid_chunk(chunk: &[u8; 8]) -> bool {
+    is_valid(u64::from_be_bytes(*chunk))
+}
+
+#[inline]
+const fn is_valid(x: u64) -> bool {
+    if x & HIGH_BITS != 0 {
+        // Non-ASCII characters.
+        return false;
+    }
+    let digit = ge(x, b'0') & !ge(x, b'9' + 1);
+    let letter = ge(x, b'a') & !ge(x, b'z' + 1);
+    let excluded = eq(x, b'i') | eq(x, b'l') | eq(x, b'o') | eq(x, b'u');
+    (digit | (letter & !excluded)) & ONES == ONES
+}
+
+/// Returns `0x01` in each byte of `x` that is greater than or equal to `k` and `0x00` otherwise.
+///
+/// Each byte of `x` should be less than `0x80`, `k` should be less than or equal to `0x80`.
+#[inline]
+const fn ge(x: u64, k: u8) -> u64 {
+    ((x + splat(0x80 - k)) & HIGH_BITS) >> 7
+}
+
+/// Returns `0x01` in each byte of `x` that is equal to `k` and `0x00` otherwise.
+///
+/// Each byte of `x` should be less than `0x80`, `k` should be less than `0x80`.
+#[inline]
+const fn eq(x: u64, k: u8) -> u64 {
+    ge(x, k) ^ ge(x, k + 1)
+}
+
+/// Returns `u64` with each byte equal to `b`.
+#[inline]
+const fn splat(b: u8) -> u64 {
+    u64::from_ne_bytes([b; 8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbtest::arbtest;
+
+    use crate::decode_chunk_scalar;
+    use crate::encode_chunk_scalar;
+    use crate::is_valid_char;
+    use crate::CHARS;
+
+    #[test]
+    fn test_encode_chunk() {
+        arbtest(|u| {
+            let chunk: [u8; 5] = u.arbitrary()?;
+            assert_eq!(
+                encode_chunk_scalar(&chunk),
+                encode_chunk(&chunk),
+                "chunk = {chunk:?}"
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_decode_chunk() {
+        arbtest(|u| {
+            let mut chunk = [0_u8; 8];
+            for ch in chunk.iter_mut() {
+                *ch = if u.ratio(1, 16)? {
+                    u.arbitrary()?
+                } else {
+                    *u.choose(&CHARS)?
+                };
+            }
+            let expected = chunk
+                .iter()
+                .all(|ch| is_valid_char(*ch))
+                .then(|| decode_chunk_scalar(&chunk));
+            assert_eq!(expected, decode_chunk(&chunk), "chunk = {chunk:?}");
+            assert_eq!(
+                expected.is_some(),
+                is_valid_chunk(&chunk),
+                "chunk = {chunk:?}"
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_is_valid_chunk_exhaustive() {
+        for i in 0..8 {
+            for ch in 0..=u8::MAX {
+                let mut chunk = [b'0'; 8];
+                chunk[i] = ch;
+                assert_eq!(
+                    is_valid_char(ch),
+                    is_valid_chunk(&chunk),
+                    "chunk = {chunk:?}"
+                );
+            }
+        }
+    }
+}
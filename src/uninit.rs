@@ -0,0 +1,206 @@
+use core::mem::MaybeUninit;
+
+use crate::decode;
+use crate::encode;
+use crate::DecodeError;
+use crate::IntoInput;
+use crate::Output;
+use crate::OutputFull;
+use crate::TryOutput;
+
+/// An implementation of [`Output`] for uninitialized buffers.
+///
+/// Like `&mut [u8]` this output panics if the buffer is too small.
+pub struct UninitOutput<'a> {
+    buf: &'a mut [MaybeUninit<u8>],
+    len: usize,
+}
+
+impl<'a> UninitOutput<'a> {
+    /// Create new output from the supplied buffer.
+    pub const fn new(buf: &'a mut [MaybeUninit<u8>]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Returns the number of initialized bytes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no bytes were initialized.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Transform into initialized part of the buffer.
+    pub fn into_initialized(self) -> &'a mut [u8] {
+        let initialized = &mut self.buf[..self.len];
+        // SAFETY: The first `len` bytes were initialized by `push` and `push_slice`, and
+        // `MaybeUninit<u8>` has the same layout as `u8`.
+        unsafe { &mut *(initialized as *mut [MaybeUninit<u8>] as *mut [u8]) }
+    }
+}
+
+impl Output for UninitOutput<'_> {
+    fn push(&mut self, ch: u8) {
+        self.buf[self.len].write(ch);
+        self.len += 1;
+    }
+
+    #[inline]
+    fn push_slice(&mut self, bytes: &[u8]) {
+        let dst = &mut self.buf[self.len..][..bytes.len()];
+        for (dst, src) in dst.iter_mut().zip(bytes) {
+            dst.write(*src);
+        }
+        self.len += bytes.len();
+    }
+}
+
+impl TryOutput for UninitOutput<'_> {
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull> {
+        let available = self.buf.len() - self.len;
+        if available < additional {
+            return Err(OutputFull::new(available, additional));
+        }
+        Ok(())
+    }
+}
+
+/// Encode `input` byte sequence using BASE32 encoding into uninitialized buffer.
+///
+/// Returns the initialized part of the buffer. Panics if the buffer is too small.
+pub fn encode_uninit<I: IntoInput<5>>(input: I, output: &mut [MaybeUninit<u8>]) -> &mut str {
+    let mut output = UninitOutput::new(output);
+    encode(input, &mut output);
+    let chars = output.into_initialized();
+    // SAFETY: BASE32-encoded string contains only ASCII characters.
+    unsafe { core::str::from_utf8_unchecked_mut(chars) }
+}
+
+/// Decode `input` byte sequence using BASE32 encoding into uninitialized buffer.
+///
+/// Returns the initialized part of the buffer. Panics if the buffer is too small.
+pub fn decode_uninit<I: IntoInput<8>>(
+    input: I,
+    output: &mut [MaybeUninit<u8>],
+) -> Result<&mut [u8], DecodeError> {
+    let mut output = UninitOutput::new(output);
+    decode(input, &mut output)?;
+    Ok(output.into_initialized())
+}
+
+/// Encode `input` byte sequence using BASE32 encoding into the spare capacity of `output`.
+///
+/// Reserves the required capacity in advance and returns the appended string.
+///
+/// ```rust
+/// use base32_fs::encode_into_spare_capacity;
+///
+/// let mut output = b"hash=".to_vec();
+/// assert_eq!("d1jprv3f", encode_into_spare_capacity(b"hello", &mut output));
+/// assert_eq!(b"hash=d1jprv3f", output.as_slice());
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encode_into_spare_capacity<'a>(
+    input: &[u8],
+    output: &'a mut alloc::vec::Vec<u8>,
+) -> &'a mut str {
+    let start = output.len();
+    let len = crate::encoded_len(input.len());
+    output.reserve(len);
+    let n = encode_uninit(input, &mut output.spare_capacity_mut()[..len]).len();
+    // SAFETY: `encode_uninit` initialized `n` bytes of the spare capacity.
+    unsafe { output.set_len(start + n) };
+    // SAFETY: BASE32-encoded string contains only ASCII characters.
+    unsafe { core::str::from_utf8_unchecked_mut(&mut output[start..]) }
+}
+
+/// Decode `input` byte sequence using BASE32 encoding into the spare capacity of `output`.
+///
+/// Reserves the required capacity in advance and returns the appended bytes. On error `output`
+/// contains the bytes decoded so far.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn decode_into_spare_capacity<'a>(
+    input: &[u8],
+    output: &'a mut alloc::vec::Vec<u8>,
+) -> Result<&'a mut [u8], DecodeError> {
+    let start = output.len();
+    // The upper bound of the decoded length.
+    let len = input.len() / 8 * 5 + 4;
+    output.reserve(len);
+    let mut uninit = UninitOutput::new(&mut output.spare_capacity_mut()[..len]);
+    let result = decode(input, &mut uninit);
+    let n = uninit.len();
+    // SAFETY: `decode` initialized `n` bytes of the spare capacity.
+    unsafe { output.set_len(start + n) };
+    result?;
+    Ok(&mut output[start..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use arbtest::arbtest;
+
+    #[test]
+    fn test_uninit() {
+        arbtest(|u| {
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected: Vec<u8> = Vec::new();
+            encode(&input, &mut expected);
+            let mut buf = [MaybeUninit::uninit(); 1000];
+            let chars = encode_uninit(&input, &mut buf[..expected.len()]);
+            assert_eq!(&expected[..], chars.as_bytes());
+            let mut bytes = [MaybeUninit::uninit(); 1000];
+            let actual = decode_uninit(chars.as_bytes(), &mut bytes[..input.len()]).unwrap();
+            assert_eq!(&input[..], actual);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_spare_capacity() {
+        arbtest(|u| {
+            let prefix: Vec<u8> = u.arbitrary()?;
+            let input: Vec<u8> = u.arbitrary()?;
+            let mut expected = prefix.clone();
+            encode(&input, &mut expected);
+            let mut actual = prefix.clone();
+            let chars = encode_into_spare_capacity(&input, &mut actual);
+            assert_eq!(&expected[prefix.len()..], chars.as_bytes());
+            assert_eq!(expected, actual);
+            let mut expected = prefix.clone();
+            let expected_result = decode(&input[..], &mut expected);
+            let mut actual = prefix.clone();
+            let actual_result = decode_into_spare_capacity(&input, &mut actual);
+            match actual_result {
+                Ok(bytes) => assert_eq!(&expected[prefix.len()..], bytes),
+                Err(e) => assert_eq!(expected_result, Err(e)),
+            }
+            assert_eq!(expected, actual);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut buf = [MaybeUninit::uninit(); 7];
+        let mut output = UninitOutput::new(&mut buf);
+        assert_eq!(
+            Err(OutputFull::new(7, 8)),
+            crate::try_encode(b"hello", &mut output)
+        );
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_small() {
+        let mut buf = [MaybeUninit::uninit(); 7];
+        encode_uninit(b"hello", &mut buf);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::DecodeError;
+use crate::EncodeOutput;
+use crate::Input;
+use crate::IntoInput;
+use crate::OutputFull;
+use crate::SeparatedOutput;
+use crate::SkipInput;
+use crate::TryEncodeOutput;
+
+/// An [`EncodeOutput`] adapter that wraps the lines at `line_len` characters.
+///
+/// The line ending is inserted between the lines, i.e. the last line is not terminated. Use
+/// [`WhitespaceInput`] to decode the result.
+///
+/// ```rust
+/// use base32_fs::{encode, WrappedOutput};
+///
+/// let mut output = String::new();
+/// encode(b"hello", &mut WrappedOutput::new(&mut output, 3, b"\n"));
+/// assert_eq!("d1j\nprv\n3f", output);
+/// ```
+pub struct WrappedOutput<'a, O: ?Sized> {
+    inner: SeparatedOutput<'a, O, &'static [u8]>,
+}
+
+impl<'a, O: EncodeOutput + ?Sized> WrappedOutput<'a, O> {
+    /// Create new output that writes to `output` and inserts `line_ending` every `line_len`
+    /// characters.
+    ///
+    /// Panics if `line_len` is zero or if `line_ending` is not ASCII.
+    pub const fn new(output: &'a mut O, line_len: usize, line_ending: &'static [u8]) -> Self {
+        assert!(line_len != 0, "The line length is zero");
+        assert!(line_ending.is_ascii(), "The line ending is not ASCII");
+        Self {
+            inner: SeparatedOutput::new(output, line_len, line_ending),
+        }
+    }
+}
+
+impl<O: EncodeOutput + ?Sized> EncodeOutput for WrappedOutput<'_, O> {
+    #[inline]
+    fn push_chars(&mut self, chars: &[u8]) {
+        self.inner.push_chars(chars);
+    }
+}
+
+impl<O: TryEncodeOutput + ?Sized> TryEncodeOutput for WrappedOutput<'_, O> {
+    fn try_reserve(&mut self, additional: usize) -> Result<(), OutputFull> {
+        self.inner.try_reserve(additional)
+    }
+}
+
+/// An [`Input`] adapter that skips ASCII whitespace including line endings.
+///
+/// The errors report the positions in the original input including the whitespace, i.e. length
+/// errors report the position after the last non-whitespace character.
+///
+/// ```rust
+/// use base32_fs::{decode, WhitespaceInput};
+///
+/// let mut output: Vec<u8> = Vec::new();
+/// decode(WhitespaceInput::new(b"d1j\r\nprv\r\n3f\r\n"), &mut output).unwrap();
+/// assert_eq!(b"hello", output.as_slice());
+/// ```
+#[derive(Clone)]
+pub struct WhitespaceInput<I> {
+    inner: SkipInput<I>,
+}
+
+impl<I: Input<8>> WhitespaceInput<I> {
+    /// Create new input that reads from `input` and skips ASCII whitespace.
+    pub fn new<T: IntoInput<8, Input = I>>(input: T) -> Self {
+        Self {
+            inner: SkipInput::new(input.into_input()),
+        }
+    }
+}
+
+impl<I: Input<8>> Input<8> for WhitespaceInput<I> {
+    fn next_chunk(&mut self) -> Option<&[u8]> {
+        self.inner
+            .next_chunk(|ch| (!ch.is_ascii_whitespace()).then_some(ch))
+    }
+
+    fn remainder(&self) -> &[u8] {
+        self.inner.remainder()
+    }
+
+    fn map_error(&self, error: DecodeError) -> DecodeError {
+        self.inner.map_error(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use arbtest::arbtest;
+
+    use crate::decode;
+    use crate::group::tests::check_separated;
+    use crate::group::tests::check_skip_errors;
+    use crate::DecodeErrorKind;
+
+    #[test]
+    fn test_wrapped() {
+        arbtest(|u| {
+            let line_ending: &'static [u8] = if u.arbitrary()? { b"\r\n" } else { b"\n" };
+            check_separated(
+                u,
+                line_ending,
+                |output, line_len, f| f(&mut WrappedOutput::new(output, line_len, line_ending)),
+                |input, output| decode(WhitespaceInput::new(input), output),
+            )
+        });
+    }
+
+    #[test]
+    fn test_whitespace_errors() {
+        arbtest(|u| {
+            check_skip_errors(
+                u,
+                |ch| ch.is_ascii_whitespace(),
+                |input, output| decode(WhitespaceInput::new(input), output),
+            )
+        });
+    }
+
+    #[test]
+    fn test_error_index() {
+        let mut output: Vec<u8> = Vec::new();
+        let e = decode(WhitespaceInput::new(b"d1jp\nrv3i\n"), &mut output).unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidChar, e.kind());
+        assert_eq!(8, e.index());
+        let e = decode(WhitespaceInput::new(b" d1jp\r\nrv3f\t01"), &mut output).unwrap_err();
+        assert_eq!(DecodeErrorKind::NonCanonical, e.kind());
+        assert_eq!(13, e.index());
+        let e = decode(WhitespaceInput::new(b"d1jp\r\nrv3f\r\n0\r\n"), &mut output).unwrap_err();
+        assert_eq!(DecodeErrorKind::InvalidLength, e.kind());
+        assert_eq!(13, e.index());
+    }
+}